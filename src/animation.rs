@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_aseprite::anim::AsepriteAnimation;
 
 use crate::player::{Player, PlayerState};
 use crate::state::GameState;
@@ -8,6 +9,13 @@ pub struct AnimationPlugin;
 #[derive(Component, Deref, DerefMut)]
 pub struct AnimationTimer(pub Timer);
 
+/// Characters drawn from the global texture atlas: 4 idle frames starting at `first`,
+/// followed by 4 run frames.
+#[derive(Component)]
+pub struct AtlasAnimation {
+    pub first: usize,
+}
+
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app
@@ -16,6 +24,7 @@ impl Plugin for AnimationPlugin {
                 (
                     animation_timer_tick,
                     animate_player,
+                    switch_player_aseprite_tag,
                     // animate_enemy,
                 ).run_if(in_state(GameState::InGame)),
             );
@@ -33,22 +42,34 @@ fn animation_timer_tick(
 
 fn animate_player(
     time: Res<Time>,
-    mut player_query: Query<(&mut TextureAtlas, &AnimationTimer, &PlayerState), With<Player>>,
+    mut player_query: Query<(&mut TextureAtlas, &AnimationTimer, &AtlasAnimation, &PlayerState), With<Player>>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
-    let (mut texture, timer, player_state) = player_query.single_mut();
+    let (mut texture, timer, animation, player_state) = player_query.single_mut();
     if timer.just_finished() {
         let base_sprite_index = match player_state {
-            PlayerState::Idle => 0,
-            PlayerState::Run => 4,
+            PlayerState::Idle => animation.first,
+            PlayerState::Run => animation.first + 4,
         };
         texture.index = base_sprite_index + (texture.index + 1) % 4;
     }
 }
 
+/// Characters drawn from an Aseprite file play its `idle` and `run` tags.
+fn switch_player_aseprite_tag(
+    mut player_query: Query<(&mut AsepriteAnimation, &PlayerState), (With<Player>, Changed<PlayerState>)>,
+) {
+    for (mut animation, player_state) in player_query.iter_mut() {
+        *animation = AsepriteAnimation::from(match player_state {
+            PlayerState::Idle => "idle",
+            PlayerState::Run => "run",
+        });
+    }
+}
+
 // fn animate_enemy(
 //     mut enemy_query: Query<(&mut TextureAtlas, &AnimationTimer), With<Enemy>>,
 // ) {
@@ -1,17 +1,29 @@
 use bevy::{math::vec3, prelude::*};
+use bevy::transform::TransformSystem;
 
 use crate::player::Player;
 use crate::state::GameState;
 
 pub struct CameraPlugin;
 
+/// World space rectangle seen by the camera, updated once transforms are propagated.
+#[derive(Resource, Default)]
+pub struct CameraView(pub Rect);
+
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<CameraView>()
             .add_systems(OnEnter(GameState::Loading), setup_camera)
             .add_systems(
                 Update,
                 camera_follow_player.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                update_camera_view
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -33,4 +45,23 @@ fn camera_follow_player(
     let (x, y) = (player_transform.x, player_transform.y);
 
     camera_transform.translation = camera_transform.translation.lerp(vec3(x, y, 0.0), 0.05);
-}
\ No newline at end of file
+}
+
+fn update_camera_view(
+    mut view: ResMut<CameraView>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+) {
+    if camera_query.is_empty() {
+        return;
+    }
+
+    let (camera, camera_transform) = camera_query.single();
+    let Some(viewport) = camera.logical_viewport_rect() else {
+        return;
+    };
+
+    let corners = [viewport.min, viewport.max].map(|corner| camera.viewport_to_world_2d(camera_transform, corner));
+    if let [Some(a), Some(b)] = corners {
+        view.0 = Rect::from_corners(a, b);
+    }
+}
@@ -0,0 +1,341 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use belly::prelude::*;
+use belly::widgets::common::Label;
+use bevy_aseprite::anim::AsepriteAnimation;
+use leafwing_manifest::identifier::Id;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::difficulty::MonsterScaling;
+use crate::characters::monsters::{spawn_monster, Monster, MonsterData, MonsterManifest, MONSTER_Z_INDEX};
+use crate::characters::ranged::{spawn_monster_projectile, MonsterProjectile};
+use crate::common::motion::Velocity;
+use crate::common::vitals::Health;
+use crate::consts::*;
+use crate::player::Player;
+use crate::resources::GlobalTextureAtlas;
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::world::RunTimer;
+
+pub(crate) struct BossesPlugin;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BossData {
+    /// Run time, in seconds, at which the boss spawns
+    pub spawn_time: f32,
+    /// Sprite scale, regular monsters use 3.0
+    pub scale: f32,
+    /// Ordered from the first phase (`health_threshold: 1.0`) to the last one
+    pub phases: Vec<BossPhaseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BossPhaseData {
+    /// The phase starts once the boss health fraction drops to this value or below
+    pub health_threshold: f32,
+    /// Aseprite animation tag played during the phase
+    pub animation: String,
+    #[serde(default = "default_speed_multiplier")]
+    pub speed_multiplier: f32,
+    /// Seconds between two special attacks
+    pub attack_interval: f32,
+    /// Special attacks, used in turn
+    pub attacks: Vec<BossAttack>,
+}
+
+fn default_speed_multiplier() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum BossAttack {
+    /// Fires `count` projectiles evenly spread around the boss
+    RadialBurst { count: usize, projectile_speed: f32, damage: f32, projectile_sprite_index: usize },
+    /// Rushes towards the player's current position
+    Charge { speed: f32, duration: f32 },
+    /// Spawns `count` monsters of the given manifest name around the boss
+    Summon { monster: String, count: usize },
+}
+
+#[derive(Debug, Component)]
+pub(crate) struct Boss {
+    pub phase: usize,
+    pub base_speed: f32,
+    pub next_attack: usize,
+    /// Run time at which the boss uses its next special attack
+    pub next_attack_at: f32,
+}
+
+/// Boss rushing in a straight line, it ignores the regular chase logic meanwhile.
+#[derive(Debug, Component)]
+pub(crate) struct Charging {
+    pub velocity: Vec2,
+    pub until: f32,
+}
+
+/// Bosses already spawned during the current run.
+#[derive(Resource, Default)]
+struct SpawnedBosses(HashSet<Id<MonsterData>>);
+
+#[derive(Component, Default)]
+struct BossBarName;
+
+#[derive(Component, Default)]
+struct BossBarFill;
+
+impl Plugin for BossesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SpawnedBosses>()
+            .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(OnEnter(GameState::GameInit), (reset_spawned_bosses, spawn_boss_bar))
+            .add_systems(OnEnter(GameState::MainMenu), despawn_boss_bar)
+            .add_systems(
+                Update,
+                (
+                    spawn_bosses,
+                    update_boss_phase,
+                    handle_boss_attacks,
+                    update_charging_bosses,
+                    update_boss_bar,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn load_assets(
+    mut commands: Commands,
+) {
+    commands.add(StyleSheet::load("styles/bossbar.css"));
+}
+
+fn reset_spawned_bosses(mut spawned_bosses: ResMut<SpawnedBosses>) {
+    spawned_bosses.0.clear();
+}
+
+fn spawn_boss_bar(
+    mut commands: Commands,
+) {
+    let boss_name = commands.spawn_empty().id();
+    let boss_fill = commands.spawn_empty().id();
+    commands.add(eml! {
+        <div id="bossbar" c:bossbar c:hidden>
+            <label {boss_name} with=BossBarName c:bossbar-name/>
+            <div c:bossbar-background>
+                <div {boss_fill} with=BossBarFill c:bossbar-fill/>
+            </div>
+        </div>
+    });
+}
+
+fn despawn_boss_bar(
+    mut elements: Elements,
+) {
+    elements.select("#bossbar").remove();
+}
+
+fn spawn_bosses(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    monster_manifest: Res<MonsterManifest>,
+    mut spawned_bosses: ResMut<SpawnedBosses>,
+    player_query: Query<&Transform, With<Player>>,
+    scaling: Res<MonsterScaling>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let now = run_timer.elapsed_secs();
+    let player_pos = player_query.single().translation.truncate();
+
+    for (monster_id, monster_data) in monster_manifest.0.iter() {
+        let Some(boss) = &monster_data.boss else {
+            continue;
+        };
+        if now < boss.spawn_time || spawned_bosses.0.contains(monster_id) {
+            continue;
+        }
+        spawned_bosses.0.insert(*monster_id);
+
+        let angle = run_rng.spawning.gen_range(0.0..PI * 2.0);
+        let position = player_pos + Vec2::from_angle(angle) * BOSS_SPAWN_DISTANCE;
+        let mut monster = spawn_monster(&mut commands, *monster_id, monster_data, position, &scaling);
+        monster.insert(Boss {
+            phase: 0,
+            base_speed: monster_data.speed * scaling.speed,
+            next_attack: 0,
+            next_attack_at: now + boss.phases.first().map_or(0.0, |phase| phase.attack_interval),
+        });
+        monster.insert(Transform::from_translation(position.extend(MONSTER_Z_INDEX)).with_scale(Vec3::splat(boss.scale)));
+        if let Some(phase) = boss.phases.first() {
+            monster.insert(AsepriteAnimation::from(phase.animation.as_str()));
+        }
+    }
+}
+
+fn update_boss_phase(
+    monster_manifest: Res<MonsterManifest>,
+    mut boss_query: Query<(&mut Boss, &mut Monster, &mut AsepriteAnimation, &Health)>,
+) {
+    for (mut boss, mut monster, mut animation, health) in boss_query.iter_mut() {
+        let Some(boss_data) = monster_manifest.0.get(&monster.id).and_then(|data| data.boss.as_ref()) else {
+            continue;
+        };
+
+        let fraction = health.fraction();
+        let phase = boss_data.phases
+            .iter()
+            .rposition(|phase| fraction <= phase.health_threshold)
+            .unwrap_or(0);
+
+        if phase == boss.phase {
+            continue;
+        }
+
+        let phase_data = &boss_data.phases[phase];
+        boss.phase = phase;
+        boss.next_attack = 0;
+        monster.speed = boss.base_speed * phase_data.speed_multiplier;
+        *animation = AsepriteAnimation::from(phase_data.animation.as_str());
+    }
+}
+
+fn handle_boss_attacks(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    handle: Res<GlobalTextureAtlas>,
+    monster_manifest: Res<MonsterManifest>,
+    scaling: Res<MonsterScaling>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&mut Boss, &Monster, &Transform, Entity), Without<Player>>,
+) {
+    if player_query.is_empty() || boss_query.is_empty() {
+        return;
+    }
+
+    let now = run_timer.elapsed_secs();
+    let player_pos = player_query.single().translation.truncate();
+
+    for (mut boss, monster, transform, entity) in boss_query.iter_mut() {
+        if now < boss.next_attack_at {
+            continue;
+        }
+
+        let Some(phase) = monster_manifest.0
+            .get(&monster.id)
+            .and_then(|data| data.boss.as_ref())
+            .and_then(|boss_data| boss_data.phases.get(boss.phase)) else {
+            continue;
+        };
+        boss.next_attack_at = now + phase.attack_interval;
+
+        if phase.attacks.is_empty() {
+            continue;
+        }
+        let attack = &phase.attacks[boss.next_attack % phase.attacks.len()];
+        boss.next_attack += 1;
+
+        let boss_pos = transform.translation.truncate();
+        let on_hit = monster_manifest.0
+            .get(&monster.id)
+            .map(|monster_data| monster_data.on_hit.clone())
+            .unwrap_or_default();
+        match attack {
+            BossAttack::RadialBurst { count, projectile_speed, damage, projectile_sprite_index } => {
+                for i in 0..*count {
+                    let angle = i as f32 * PI * 2.0 / *count as f32;
+                    spawn_monster_projectile(
+                        &mut commands,
+                        &handle,
+                        *projectile_sprite_index,
+                        boss_pos,
+                        MonsterProjectile {
+                            damage: *damage * scaling.damage,
+                            velocity: Vec2::from_angle(angle) * *projectile_speed,
+                            expires_at: now + BOSS_PROJECTILE_LIFETIME,
+                            source: entity,
+                            on_hit: on_hit.clone(),
+                        },
+                    );
+                }
+            }
+            BossAttack::Charge { speed, duration } => {
+                let direction = (player_pos - boss_pos).normalize_or_zero();
+                commands.entity(entity).insert(Charging {
+                    velocity: direction * *speed,
+                    until: now + duration,
+                });
+            }
+            BossAttack::Summon { monster, count } => {
+                let summon_id = Id::from_name(monster);
+                let Some(summon_data) = monster_manifest.0.get(&summon_id) else {
+                    warn!("Boss tried to summon unknown monster {}", monster);
+                    continue;
+                };
+
+                for i in 0..*count {
+                    let angle = i as f32 * PI * 2.0 / *count as f32;
+                    let position = boss_pos + Vec2::from_angle(angle) * BOSS_SUMMON_RADIUS;
+                    spawn_monster(&mut commands, summon_id, summon_data, position, &scaling);
+                }
+            }
+        }
+    }
+}
+
+fn update_charging_bosses(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    mut boss_query: Query<(&Charging, &mut Velocity, Entity)>,
+) {
+    let now = run_timer.elapsed_secs();
+
+    for (charging, mut velocity, entity) in boss_query.iter_mut() {
+        if now >= charging.until {
+            commands.entity(entity).remove::<Charging>();
+            continue;
+        }
+
+        velocity.0 = charging.velocity;
+    }
+}
+
+fn update_boss_bar(
+    boss_query: Query<(&Health, &Monster), With<Boss>>,
+    monster_manifest: Res<MonsterManifest>,
+    mut name_query: Query<&mut Label, With<BossBarName>>,
+    mut fill_query: Query<&mut Style, With<BossBarFill>>,
+    mut elements: Elements,
+    mut visible: Local<bool>,
+) {
+    // Only the first boss alive is displayed
+    let Some((health, monster)) = boss_query.iter().next() else {
+        if *visible {
+            *visible = false;
+            elements.select("#bossbar").add_class("hidden");
+        }
+        return;
+    };
+    if !*visible {
+        *visible = true;
+        elements.select("#bossbar").remove_class("hidden");
+    }
+
+    if let Ok(mut label) = name_query.get_single_mut() {
+        if let Some(monster_data) = monster_manifest.0.get(&monster.id) {
+            if label.value != monster_data.name {
+                label.value = monster_data.name.clone();
+            }
+        }
+    }
+
+    if let Ok(mut style) = fill_query.get_single_mut() {
+        style.width = Val::Percent(health.fraction() * 100.0);
+    }
+}
@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::state::GameState;
+use crate::world::RunTimer;
+
+/// Difficulty picked when the player never changed it
+pub const DEFAULT_DIFFICULTY: &str = "Normal";
+
+pub(crate) struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<DifficultyManifest>("difficulty/data.ron")
+            .init_resource::<SelectedDifficulty>()
+            .init_resource::<MonsterScaling>()
+            .add_systems(OnEnter(GameState::GameInit), reset_monster_scaling)
+            // Monsters are spawned in `PostUpdate`, with the scaling of the current frame
+            .add_systems(Update, update_monster_scaling.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// Multiplier applied to a monster stat, as a function of the run time in minutes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Curve {
+    /// Interpolated between `(minute, multiplier)` points, constant before the first and after the last one
+    Linear(Vec<(f32, f32)>),
+    /// `start * growth ^ minutes`, capped at `max` when set
+    Exponential {
+        start: f32,
+        growth: f32,
+        #[serde(default)]
+        max: Option<f32>,
+    },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear(Vec::new())
+    }
+}
+
+impl Curve {
+    pub fn at(&self, minutes: f32) -> f32 {
+        match self {
+            Curve::Linear(points) => {
+                let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                    return 1.0;
+                };
+                if minutes <= first.0 {
+                    return first.1;
+                }
+                points
+                    .windows(2)
+                    .find(|segment| minutes < segment[1].0)
+                    .map_or(last.1, |segment| {
+                        let ((start, from), (end, to)) = (segment[0], segment[1]);
+                        from + (to - from) * (minutes - start) / (end - start)
+                    })
+            }
+            Curve::Exponential { start, growth, max } => {
+                let value = start * growth.powf(minutes);
+                max.map_or(value, |max| value.min(max))
+            }
+        }
+    }
+
+    /// Linear points must be ordered by minute for `at` to interpolate between neighbours
+    fn sorted(mut self) -> Self {
+        if let Curve::Linear(points) = &mut self {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        self
+    }
+}
+
+/// Preset scaling monsters over the run.
+#[derive(Debug, PartialEq)]
+pub(crate) struct DifficultyData {
+    pub name: String,
+    pub description: String,
+    pub health: Curve,
+    pub speed: Curve,
+    pub damage: Curve,
+    /// Multiplies the spawn rate of the stage waves
+    pub spawn_rate: Curve,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawDifficultyData {
+    name: String,
+    description: String,
+    #[serde(default)]
+    health: Curve,
+    #[serde(default)]
+    speed: Curve,
+    #[serde(default)]
+    damage: Curve,
+    #[serde(default)]
+    spawn_rate: Curve,
+}
+
+/// Difficulties in the order they are offered to the player.
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct DifficultyManifest(pub Vec<DifficultyData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawDifficultyManifest(Vec<RawDifficultyData>);
+
+impl Manifest for DifficultyManifest {
+    type RawManifest = RawDifficultyManifest;
+    type RawItem = RawDifficultyData;
+    type Item = DifficultyData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let difficulties = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| DifficultyData {
+                name: raw_item.name,
+                description: raw_item.description,
+                health: raw_item.health.sorted(),
+                speed: raw_item.speed.sorted(),
+                damage: raw_item.damage.sorted(),
+                spawn_rate: raw_item.spawn_rate.sorted(),
+            })
+            .collect();
+
+        Ok(DifficultyManifest(difficulties))
+    }
+
+    fn get(&self, id: Id<DifficultyData>) -> Option<&Self::Item> {
+        self.0.iter().find(|difficulty| Id::from_name(&difficulty.name) == id)
+    }
+}
+
+/// Difficulty of the next run, picked on the character select screen.
+#[derive(Resource, Debug)]
+pub(crate) struct SelectedDifficulty(pub Id<DifficultyData>);
+
+impl Default for SelectedDifficulty {
+    fn default() -> Self {
+        Self(Id::from_name(DEFAULT_DIFFICULTY))
+    }
+}
+
+/// Multipliers applied to the `MonsterData` of monsters spawned right now.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MonsterScaling {
+    pub health: f32,
+    pub speed: f32,
+    pub damage: f32,
+    pub spawn_rate: f32,
+}
+
+impl Default for MonsterScaling {
+    fn default() -> Self {
+        Self {
+            health: 1.0,
+            speed: 1.0,
+            damage: 1.0,
+            spawn_rate: 1.0,
+        }
+    }
+}
+
+fn reset_monster_scaling(mut scaling: ResMut<MonsterScaling>) {
+    *scaling = MonsterScaling::default();
+}
+
+fn update_monster_scaling(
+    run_timer: Res<RunTimer>,
+    selected: Res<SelectedDifficulty>,
+    difficulty_manifest: Res<DifficultyManifest>,
+    mut scaling: ResMut<MonsterScaling>,
+) {
+    let Some(difficulty) = difficulty_manifest.get(selected.0) else {
+        return;
+    };
+
+    let minutes = run_timer.elapsed_secs() / 60.0;
+    scaling.set_if_neq(MonsterScaling {
+        health: difficulty.health.at(minutes),
+        speed: difficulty.speed.at(minutes),
+        damage: difficulty.damage.at(minutes),
+        spawn_rate: difficulty.spawn_rate.at(minutes),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear(points: &[(f32, f32)]) -> Curve {
+        Curve::Linear(points.to_vec()).sorted()
+    }
+
+    #[test]
+    fn empty_linear_curve_is_neutral() {
+        assert_eq!(linear(&[]).at(0.0), 1.0);
+        assert_eq!(linear(&[]).at(42.0), 1.0);
+    }
+
+    #[test]
+    fn linear_curve_is_constant_outside_of_its_points() {
+        let curve = linear(&[(5.0, 2.0), (10.0, 4.0)]);
+
+        assert_eq!(curve.at(0.0), 2.0);
+        assert_eq!(curve.at(5.0), 2.0);
+        assert_eq!(curve.at(10.0), 4.0);
+        assert_eq!(curve.at(60.0), 4.0);
+    }
+
+    #[test]
+    fn linear_curve_interpolates_between_points() {
+        let curve = linear(&[(0.0, 1.0), (10.0, 2.0), (30.0, 4.0)]);
+
+        assert_eq!(curve.at(5.0), 1.5);
+        assert_eq!(curve.at(20.0), 3.0);
+    }
+
+    #[test]
+    fn unsorted_linear_points_are_sorted() {
+        let curve = linear(&[(30.0, 4.0), (0.0, 1.0), (10.0, 2.0)]);
+
+        assert_eq!(curve, Curve::Linear(vec![(0.0, 1.0), (10.0, 2.0), (30.0, 4.0)]));
+        assert_eq!(curve.at(20.0), 3.0);
+    }
+
+    #[test]
+    fn exponential_curve_grows_up_to_its_cap() {
+        let uncapped = Curve::Exponential { start: 1.0, growth: 2.0, max: None };
+        let capped = Curve::Exponential { start: 1.0, growth: 2.0, max: Some(5.0) };
+
+        assert_eq!(uncapped.at(0.0), 1.0);
+        assert_eq!(uncapped.at(3.0), 8.0);
+        assert_eq!(capped.at(2.0), 4.0);
+        assert_eq!(capped.at(3.0), 5.0);
+    }
+}
@@ -0,0 +1,413 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_manifest::asset_state::SimpleAssetState;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::CameraView;
+use crate::characters::bosses::Boss;
+use crate::characters::difficulty::MonsterScaling;
+use crate::characters::elites::{make_elite, roll_elite_affixes, AffixManifest, ELITE_CHANCE};
+use crate::characters::monsters::{spawn_monster, Monster, MonsterData, MonsterManifest, MAX_NUM_MONSTERS};
+use crate::characters::placement::{distance_outside, spawn_positions, SpawnPattern};
+use crate::common::vitals::Health;
+use crate::player::{MoveDirection, Player};
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::world::RunTimer;
+
+/// Most monsters spawned in a single frame to catch up with the target population
+const MAX_SPAWNS_PER_FRAME: usize = 20;
+
+pub(crate) struct DirectorPlugin;
+
+impl Plugin for DirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<StageManifest>("waves/stage1.ron")
+            .init_resource::<WaveDirector>()
+            .add_systems(OnEnter(SimpleAssetState::Ready), validate_stage)
+            .add_systems(OnEnter(GameState::GameInit), reset_wave_director)
+            .add_systems(
+                PostUpdate,
+                (
+                    leash_monsters,
+                    spawn_bursts,
+                    spawn_wave_monsters,
+                ).chain().run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawWaveMonster {
+    monster: String,
+    weight: f32,
+}
+
+/// Monster that can spawn during a wave, picked proportionally to `weight`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaveMonster {
+    pub id: Id<MonsterData>,
+    pub name: String,
+    pub weight: f32,
+}
+
+/// Time window of the run, in seconds, with the monsters it spawns.
+#[derive(Debug, PartialEq)]
+pub(crate) struct WaveData {
+    pub name: String,
+    pub start: f32,
+    pub end: f32,
+    /// Monsters the director keeps alive during the wave
+    pub target_population: usize,
+    /// Monsters spawned per second while below the target population
+    pub spawn_rate: f32,
+    pub pattern: SpawnPattern,
+    pub monsters: Vec<WaveMonster>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawWaveData {
+    name: String,
+    start: f32,
+    end: f32,
+    target_population: usize,
+    spawn_rate: f32,
+    #[serde(default)]
+    pattern: SpawnPattern,
+    monsters: Vec<RawWaveMonster>,
+}
+
+/// Group of monsters spawned at once at a given run time, on top of the wave population.
+#[derive(Debug, PartialEq)]
+pub(crate) struct BurstData {
+    pub time: f32,
+    pub monster: WaveMonster,
+    pub count: usize,
+    pub pattern: SpawnPattern,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawBurstData {
+    time: f32,
+    monster: String,
+    count: usize,
+    #[serde(default = "default_burst_pattern")]
+    pattern: SpawnPattern,
+}
+
+fn default_burst_pattern() -> SpawnPattern {
+    SpawnPattern::Line
+}
+
+/// What happens to a monster the player has left far behind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum LeashMode {
+    /// Teleported to a fresh spawn point ahead of the player
+    #[default]
+    Relocate,
+    /// Despawned, the director spawns a new monster in its place
+    Recycle,
+}
+
+/// Keeps monsters close to the player so that outrun monsters still put pressure on them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LeashData {
+    /// Distance outside of the camera view beyond which a monster is leashed
+    pub distance: f32,
+    pub mode: LeashMode,
+}
+
+impl Default for LeashData {
+    fn default() -> Self {
+        Self {
+            distance: 800.0,
+            mode: LeashMode::default(),
+        }
+    }
+}
+
+/// Waves and bursts of a stage, ordered by time.
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct StageManifest {
+    pub name: String,
+    pub waves: Vec<WaveData>,
+    pub bursts: Vec<BurstData>,
+    pub leash: LeashData,
+}
+
+impl StageManifest {
+    /// Wave running at `time`, the last wave keeps going once the stage is over
+    pub fn wave_at(&self, time: f32) -> Option<&WaveData> {
+        self.waves
+            .iter()
+            .find(|wave| wave.start <= time && time < wave.end)
+            .or_else(|| self.waves.last().filter(|wave| time >= wave.end))
+    }
+}
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawStageManifest {
+    name: String,
+    waves: Vec<RawWaveData>,
+    #[serde(default)]
+    bursts: Vec<RawBurstData>,
+    #[serde(default)]
+    leash: LeashData,
+}
+
+fn wave_monster(monster: String, weight: f32) -> WaveMonster {
+    WaveMonster {
+        id: Id::from_name(&monster),
+        name: monster,
+        weight,
+    }
+}
+
+impl Manifest for StageManifest {
+    type RawManifest = RawStageManifest;
+    type RawItem = RawWaveData;
+    type Item = WaveData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let mut waves = raw_manifest.waves
+            .into_iter()
+            .map(|raw_item| WaveData {
+                name: raw_item.name,
+                start: raw_item.start,
+                end: raw_item.end,
+                target_population: raw_item.target_population,
+                spawn_rate: raw_item.spawn_rate,
+                pattern: raw_item.pattern,
+                monsters: raw_item.monsters
+                    .into_iter()
+                    .map(|raw_monster| wave_monster(raw_monster.monster, raw_monster.weight))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        waves.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        let mut bursts = raw_manifest.bursts
+            .into_iter()
+            .map(|raw_burst| BurstData {
+                time: raw_burst.time,
+                monster: wave_monster(raw_burst.monster, 1.0),
+                count: raw_burst.count,
+                pattern: raw_burst.pattern,
+            })
+            .collect::<Vec<_>>();
+        bursts.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Ok(StageManifest {
+            name: raw_manifest.name,
+            waves,
+            bursts,
+            leash: raw_manifest.leash,
+        })
+    }
+
+    fn get(&self, id: Id<WaveData>) -> Option<&Self::Item> {
+        self.waves.iter().find(|wave| Id::from_name(&wave.name) == id)
+    }
+}
+
+/// Progress of the director through the stage.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct WaveDirector {
+    /// Index of the next burst to spawn
+    pub next_burst: usize,
+    /// Fractional spawns accumulated from `WaveData::spawn_rate`
+    pub spawn_budget: f32,
+    /// Name of the running wave, to log wave changes
+    pub current_wave: Option<String>,
+}
+
+/// Removes monsters missing from the monster manifest from the waves and bursts of the stage.
+fn validate_stage(
+    mut stage: ResMut<StageManifest>,
+    monster_manifest: Res<MonsterManifest>,
+) {
+    let stage_name = stage.name.clone();
+    for wave in stage.waves.iter_mut() {
+        wave.monsters.retain(|monster| {
+            let known = monster_manifest.0.contains_key(&monster.id);
+            if !known {
+                error!("Wave {} of {} spawns unknown monster {}", wave.name, stage_name, monster.name);
+            }
+            known
+        });
+    }
+    stage.bursts.retain(|burst| {
+        let known = monster_manifest.0.contains_key(&burst.monster.id);
+        if !known {
+            error!("Burst at {}s of {} spawns unknown monster {}", burst.time, stage_name, burst.monster.name);
+        }
+        known
+    });
+}
+
+fn reset_wave_director(mut director: ResMut<WaveDirector>) {
+    *director = WaveDirector::default();
+}
+
+fn spawn_director_monster(
+    commands: &mut Commands,
+    monster_id: Id<MonsterData>,
+    monster_data: &MonsterData,
+    monster_pos: Vec2,
+    affix_manifest: &AffixManifest,
+    scaling: &MonsterScaling,
+    rng: &mut impl Rng,
+) {
+    let mut monster = spawn_monster(commands, monster_id, monster_data, monster_pos, scaling);
+    if rng.gen_bool(ELITE_CHANCE) {
+        let affixes = roll_elite_affixes(affix_manifest, rng);
+        make_elite(&mut monster, monster_id, monster_data, monster_pos, &affixes, scaling);
+    }
+}
+
+/// Monsters too far outside of the view are moved ahead of the player or recycled, so that they
+/// don't walk back from afar while still counting toward the population.
+fn leash_monsters(
+    mut commands: Commands,
+    stage: Res<StageManifest>,
+    mut director: ResMut<WaveDirector>,
+    view: Res<CameraView>,
+    player_query: Query<&MoveDirection, With<Player>>,
+    mut monster_query: Query<(Entity, &mut Transform, &Health), (With<Monster>, Without<Boss>)>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() || view.0.is_empty() {
+        return;
+    }
+
+    let heading = player_query.single().0;
+    let rng = &mut run_rng.spawning;
+
+    let mut leashed = monster_query
+        .iter_mut()
+        .filter(|(_, transform, health)| {
+            !health.is_dead() && distance_outside(view.0, transform.translation.truncate()) > stage.leash.distance
+        })
+        .collect::<Vec<_>>();
+    if leashed.is_empty() {
+        return;
+    }
+
+    match stage.leash.mode {
+        LeashMode::Relocate => {
+            let positions = spawn_positions(SpawnPattern::Ahead, view.0, heading, leashed.len(), rng);
+            for ((_, transform, _), position) in leashed.iter_mut().zip(positions) {
+                transform.translation = position.extend(transform.translation.z);
+            }
+        }
+        LeashMode::Recycle => {
+            for (entity, _, _) in leashed.iter() {
+                commands.entity(*entity).despawn_recursive();
+            }
+            director.spawn_budget += leashed.len() as f32;
+        }
+    }
+}
+
+/// Keeps the monster population near the target of the running wave.
+fn spawn_wave_monsters(
+    mut commands: Commands,
+    time: Res<Time>,
+    run_timer: Res<RunTimer>,
+    stage: Res<StageManifest>,
+    monster_manifest: Res<MonsterManifest>,
+    affix_manifest: Res<AffixManifest>,
+    mut director: ResMut<WaveDirector>,
+    view: Res<CameraView>,
+    player_query: Query<&MoveDirection, With<Player>>,
+    monster_query: Query<(), (With<Monster>, Without<Boss>)>,
+    scaling: Res<MonsterScaling>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    // The view is only known once the camera transform has been propagated
+    if player_query.is_empty() || view.0.is_empty() {
+        return;
+    }
+
+    let Some(wave) = stage.wave_at(run_timer.elapsed_secs()) else {
+        return;
+    };
+    if director.current_wave.as_ref() != Some(&wave.name) {
+        info!("Wave {} of {} started", wave.name, stage.name);
+        director.current_wave = Some(wave.name.clone());
+        director.spawn_budget = 0.0;
+    }
+
+    let population = monster_query.iter().count();
+    let target = wave.target_population.min(MAX_NUM_MONSTERS);
+    if population >= target || wave.monsters.is_empty() {
+        director.spawn_budget = 0.0;
+        return;
+    }
+
+    director.spawn_budget += wave.spawn_rate * scaling.spawn_rate * time.delta_seconds();
+    let spawn_count = (director.spawn_budget.floor() as usize)
+        .min(target - population)
+        .min(MAX_SPAWNS_PER_FRAME);
+    if spawn_count == 0 {
+        return;
+    }
+    director.spawn_budget -= spawn_count as f32;
+
+    let heading = player_query.single().0;
+    let rng = &mut run_rng.spawning;
+
+    for position in spawn_positions(wave.pattern, view.0, heading, spawn_count, rng) {
+        let Ok(wave_monster) = wave.monsters.choose_weighted(rng, |monster| monster.weight) else {
+            return;
+        };
+        let Some(monster_data) = monster_manifest.0.get(&wave_monster.id) else {
+            continue;
+        };
+        spawn_director_monster(&mut commands, wave_monster.id, monster_data, position, &affix_manifest, &scaling, rng);
+    }
+}
+
+fn spawn_bursts(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    stage: Res<StageManifest>,
+    monster_manifest: Res<MonsterManifest>,
+    affix_manifest: Res<AffixManifest>,
+    mut director: ResMut<WaveDirector>,
+    view: Res<CameraView>,
+    player_query: Query<&MoveDirection, With<Player>>,
+    scaling: Res<MonsterScaling>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() || view.0.is_empty() {
+        return;
+    }
+
+    let heading = player_query.single().0;
+    let now = run_timer.elapsed_secs();
+    let rng = &mut run_rng.spawning;
+
+    while let Some(burst) = stage.bursts.get(director.next_burst).filter(|burst| burst.time <= now) {
+        director.next_burst += 1;
+
+        let Some(monster_data) = monster_manifest.0.get(&burst.monster.id) else {
+            continue;
+        };
+        info!("Burst of {} {}", burst.count, burst.monster.name);
+        for position in spawn_positions(burst.pattern, view.0, heading, burst.count, rng) {
+            spawn_director_monster(&mut commands, burst.monster.id, monster_data, position, &affix_manifest, &scaling, rng);
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::f32::consts::PI;
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::difficulty::MonsterScaling;
+use crate::characters::monsters::{spawn_monster, Monster, MonsterData, MonsterManifest, MONSTER_Z_INDEX};
+use crate::common::damage::{DamageDealt, DamageEvent, DamageKind, DamageSet, MonsterKilled};
+use crate::common::vitals::{Armor, Health};
+use crate::player::Player;
+use crate::state::GameState;
+
+/// Chance for a regular spawn to roll elite affixes
+pub const ELITE_CHANCE: f64 = 0.05;
+pub const MAX_ELITE_AFFIXES: usize = 2;
+pub const ELITE_SCALE_MULTIPLIER: f32 = 1.3;
+/// Distance from the dead elite at which split copies spawn
+pub const ELITE_SPLIT_RADIUS: f32 = 30.0;
+
+pub(crate) struct ElitesPlugin;
+
+impl Plugin for ElitesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<AffixManifest>("monsters/affixes.ron")
+            .add_systems(
+                Update,
+                (
+                    update_speed_bursts,
+                    handle_vampiric_hits,
+                ).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    split_on_death,
+                    explode_on_death,
+                ).in_set(DamageSet::Killed),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SpeedBurstData {
+    pub multiplier: f32,
+    /// Seconds a burst lasts
+    pub duration: f32,
+    /// Seconds between the start of two bursts
+    pub interval: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DeathExplosionData {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+/// Affixes rolled by an elite monster, on top of its `MonsterData`.
+#[derive(Debug, Component)]
+pub(crate) struct Elite {
+    pub affixes: Vec<Id<AffixData>>,
+}
+
+/// Base sprite tint of the elite, status effect tints take precedence over it.
+#[derive(Debug, Component, Clone, Copy)]
+pub(crate) struct EliteTint(pub Color);
+
+#[derive(Debug, Component)]
+pub(crate) struct SpeedBurst {
+    pub data: SpeedBurstData,
+    pub base_speed: f32,
+    /// Elapsed game time at which the next burst starts
+    pub next_burst_at: f32,
+    /// Elapsed game time at which the current burst ends
+    pub active_until: f32,
+}
+
+/// Fraction (0.0 to 1.0) of the damage dealt to the player healed back by the monster.
+#[derive(Debug, Component)]
+pub(crate) struct Vampiric(pub f32);
+
+/// Number of regular copies spawned when the monster dies.
+#[derive(Debug, Component)]
+pub(crate) struct SplitOnDeath(pub u32);
+
+#[derive(Debug, Component)]
+pub(crate) struct ExplodeOnDeath(pub DeathExplosionData);
+
+/// Rolls between 1 and `MAX_ELITE_AFFIXES` distinct affixes, weighted by their `weight`.
+pub(crate) fn roll_elite_affixes<'a>(
+    affix_manifest: &'a AffixManifest,
+    rng: &mut impl Rng,
+) -> Vec<(Id<AffixData>, &'a AffixData)> {
+    let affixes = affix_manifest.0.iter().collect::<Vec<_>>();
+    if affixes.is_empty() {
+        return Vec::new();
+    }
+
+    let count = rng.gen_range(1..=MAX_ELITE_AFFIXES.min(affixes.len()));
+    affixes
+        .choose_multiple_weighted(rng, count, |(_, affix)| affix.weight)
+        .map(|chosen| chosen.map(|(id, affix)| (**id, *affix)).collect())
+        .unwrap_or_default()
+}
+
+/// Turns a freshly spawned monster into an elite, overriding the stats it got from `MonsterData`.
+pub(crate) fn make_elite(
+    monster: &mut EntityCommands,
+    monster_id: Id<MonsterData>,
+    monster_data: &MonsterData,
+    position: Vec2,
+    affixes: &[(Id<AffixData>, &AffixData)],
+    scaling: &MonsterScaling,
+) {
+    if affixes.is_empty() {
+        return;
+    }
+
+    let health_multiplier: f32 = affixes.iter().map(|(_, affix)| affix.health_multiplier).product();
+    let speed_multiplier: f32 = affixes.iter().map(|(_, affix)| affix.speed_multiplier).product();
+    let defense_bonus: f32 = affixes.iter().map(|(_, affix)| affix.defense_bonus).sum();
+    let speed = monster_data.speed * scaling.speed * speed_multiplier;
+    let [r, g, b] = affixes[0].1.tint;
+
+    monster.insert((
+        Elite {
+            affixes: affixes.iter().map(|(id, _)| *id).collect(),
+        },
+        EliteTint(Color::rgb(r, g, b)),
+        Monster {
+            id: monster_id,
+            speed,
+        },
+        Health::new(monster_data.health * scaling.health * health_multiplier),
+        Armor(monster_data.defense + defense_bonus),
+        Transform::from_translation(position.extend(MONSTER_Z_INDEX)).with_scale(Vec3::splat(3.0 * ELITE_SCALE_MULTIPLIER)),
+    ));
+
+    for (_, affix) in affixes {
+        if let Some(data) = affix.speed_burst {
+            monster.insert(SpeedBurst {
+                data,
+                base_speed: speed,
+                next_burst_at: 0.0,
+                active_until: 0.0,
+            });
+        }
+        if affix.lifesteal > 0.0 {
+            monster.insert(Vampiric(affix.lifesteal));
+        }
+        if affix.split_on_death > 0 {
+            monster.insert(SplitOnDeath(affix.split_on_death));
+        }
+        if let Some(explosion) = affix.explode_on_death {
+            monster.insert(ExplodeOnDeath(explosion));
+        }
+    }
+}
+
+fn update_speed_bursts(
+    time: Res<Time>,
+    mut query: Query<(&mut SpeedBurst, &mut Monster)>,
+) {
+    let now = time.elapsed_seconds();
+
+    for (mut burst, mut monster) in query.iter_mut() {
+        if now >= burst.next_burst_at {
+            burst.active_until = now + burst.data.duration;
+            burst.next_burst_at = now + burst.data.interval;
+        }
+
+        let speed = if now < burst.active_until {
+            burst.base_speed * burst.data.multiplier
+        } else {
+            burst.base_speed
+        };
+        if monster.speed != speed {
+            monster.speed = speed;
+        }
+    }
+}
+
+/// Heals from the damage left after i-frames and armor, not from the raw hit.
+fn handle_vampiric_hits(
+    mut events: EventReader<DamageDealt>,
+    player_query: Query<(), With<Player>>,
+    mut monster_query: Query<(&mut Health, &Vampiric)>,
+) {
+    for event in events.read() {
+        if !player_query.contains(event.target) {
+            continue;
+        }
+
+        if let Ok((mut health, vampiric)) = monster_query.get_mut(event.source) {
+            health.heal(event.amount * vampiric.0);
+        }
+    }
+}
+
+fn split_on_death(
+    mut commands: Commands,
+    mut events: EventReader<MonsterKilled>,
+    monster_manifest: Res<MonsterManifest>,
+    scaling: Res<MonsterScaling>,
+    query: Query<&SplitOnDeath>,
+) {
+    for event in events.read() {
+        let Ok(split) = query.get(event.entity) else {
+            continue;
+        };
+        let Some(monster_data) = monster_manifest.0.get(&event.id) else {
+            continue;
+        };
+
+        for i in 0..split.0 {
+            let angle = i as f32 * PI * 2.0 / split.0 as f32;
+            let position = event.position + Vec2::from_angle(angle) * ELITE_SPLIT_RADIUS;
+            spawn_monster(&mut commands, event.id, monster_data, position, &scaling);
+        }
+    }
+}
+
+fn explode_on_death(
+    mut events: EventReader<MonsterKilled>,
+    query: Query<&ExplodeOnDeath>,
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    mut ew: EventWriter<DamageEvent>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+
+    for event in events.read() {
+        let Ok(explosion) = query.get(event.entity) else {
+            continue;
+        };
+
+        if event.position.distance_squared(player_pos) <= explosion.0.radius * explosion.0.radius {
+            ew.send(DamageEvent {
+                target: player,
+                amount: explosion.0.damage,
+                source: event.entity,
+                kind: DamageKind::Explosion,
+                crit: false,
+                over_time: false,
+            });
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct AffixData {
+    pub name: String,
+    /// Relative chance of being rolled
+    pub weight: f32,
+    /// Sprite tint (RGB) of elites rolling this affix first
+    pub tint: [f32; 3],
+    pub health_multiplier: f32,
+    pub speed_multiplier: f32,
+    pub defense_bonus: f32,
+    pub speed_burst: Option<SpeedBurstData>,
+    /// Fraction (0.0 to 1.0) of the damage dealt to the player healed back
+    pub lifesteal: f32,
+    pub split_on_death: u32,
+    pub explode_on_death: Option<DeathExplosionData>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawAffixData {
+    name: String,
+    weight: f32,
+    tint: [f32; 3],
+    #[serde(default = "default_multiplier")]
+    health_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    speed_multiplier: f32,
+    #[serde(default)]
+    defense_bonus: f32,
+    #[serde(default)]
+    speed_burst: Option<SpeedBurstData>,
+    #[serde(default)]
+    lifesteal: f32,
+    #[serde(default)]
+    split_on_death: u32,
+    #[serde(default)]
+    explode_on_death: Option<DeathExplosionData>,
+}
+
+fn default_multiplier() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct AffixManifest(pub HashMap<Id<AffixData>, AffixData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawAffixManifest(Vec<RawAffixData>);
+
+impl Manifest for AffixManifest {
+    type RawManifest = RawAffixManifest;
+    type RawItem = RawAffixData;
+    type Item = AffixData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let affixes: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                if !(0.0..=1.0).contains(&raw_item.lifesteal) {
+                    warn!("Lifesteal of affix {} must be a fraction, {} is clamped", raw_item.name, raw_item.lifesteal);
+                }
+
+                let item = AffixData {
+                    name: raw_item.name,
+                    weight: raw_item.weight,
+                    tint: raw_item.tint,
+                    health_multiplier: raw_item.health_multiplier,
+                    speed_multiplier: raw_item.speed_multiplier,
+                    defense_bonus: raw_item.defense_bonus,
+                    speed_burst: raw_item.speed_burst,
+                    lifesteal: raw_item.lifesteal.clamp(0.0, 1.0),
+                    split_on_death: raw_item.split_on_death,
+                    explode_on_death: raw_item.explode_on_death,
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(AffixManifest(affixes))
+    }
+
+    fn get(&self, id: Id<AffixData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
@@ -1 +1,8 @@
 pub(crate) mod monsters;
+pub(crate) mod player;
+pub(crate) mod ranged;
+pub(crate) mod bosses;
+pub(crate) mod elites;
+pub(crate) mod director;
+pub(crate) mod placement;
+pub(crate) mod difficulty;
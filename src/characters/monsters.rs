@@ -1,9 +1,6 @@
-use std::f32::consts::PI;
-use std::time::Duration;
 use bevy::prelude::*;
 use bevy::asset::{Asset, AssetServer, Handle};
-use bevy::math::vec2;
-use bevy::time::common_conditions::on_timer;
+use bevy::ecs::system::EntityCommands;
 use bevy::utils::HashMap;
 use bevy_aseprite::Aseprite;
 use bevy_aseprite::anim::AsepriteAnimation;
@@ -11,17 +8,23 @@ use leafwing_manifest::identifier::Id;
 use leafwing_manifest::manifest::{Manifest, ManifestFormat};
 use leafwing_manifest::plugin::RegisterManifest;
 use serde::{Deserialize, Serialize};
-use rand::prelude::*;
 
-use crate::common::health::Health;
+use crate::characters::bosses::{BossData, Charging};
+use crate::characters::difficulty::MonsterScaling;
+use crate::characters::ranged::{RangedAttack, RangedAttackData};
+use crate::common::motion::{Mass, MotionBundle, Stun, Velocity};
+use crate::common::status::{StatusEffectData, StatusEffects};
+use crate::common::vitals::VitalsBundle;
 use crate::player::Player;
 use crate::state::GameState;
 use crate::world::GameEntity;
 
 pub const MAX_NUM_MONSTERS: usize = 10000;
-pub const MONSTER_SPAWN_INTERVAL: f32 = 1.0;
-pub const MONSTER_SPAWN_RATE_PER_SECOND: usize = 2;
 pub const MONSTER_Z_INDEX: f32 = 9.0;
+/// Fraction of its range under which a ranged monster keeping its distance stops approaching
+pub const RANGED_HOLD_RATIO: f32 = 0.8;
+/// Fraction of its range under which a ranged monster keeping its distance backs off
+pub const RANGED_RETREAT_RATIO: f32 = 0.5;
 
 pub(crate) struct MonstersPlugin;
 
@@ -32,7 +35,6 @@ impl Plugin for MonstersPlugin {
             .add_systems(
                 PostUpdate,
                 (
-                    spawn_monsters.run_if(on_timer(Duration::from_secs_f32(1.0))),
                     move_monsters_towards_player,
                     flip_monster_sprite,
                 ).run_if(in_state(GameState::InGame)),
@@ -46,13 +48,32 @@ pub(crate) struct Monster {
     pub speed: f32,
 }
 
+/// Damage dealt to the player on contact, at most once per `cooldown` seconds.
+#[derive(Debug, Component, Clone)]
+pub(crate) struct ContactDamage {
+    pub amount: f32,
+    pub cooldown: f32,
+    /// Elapsed game time at which the monster can hit again
+    pub ready_at: f32,
+}
+
+impl ContactDamage {
+    pub fn new(amount: f32, cooldown: f32) -> Self {
+        Self { amount, cooldown, ready_at: 0.0 }
+    }
+}
+
 #[derive(Debug, Bundle)]
 pub(crate) struct MonsterBundle {
     pub monster: Monster,
     pub aseprite: Handle<Aseprite>,
     pub animation: AsepriteAnimation,
     pub transform: Transform,
-    pub health: Health,
+    pub vitals: VitalsBundle,
+    pub contact_damage: ContactDamage,
+    pub motion: MotionBundle,
+    pub mass: Mass,
+    pub status_effects: StatusEffects,
 }
 
 fn flip_monster_sprite(
@@ -74,9 +95,8 @@ fn flip_monster_sprite(
 }
 
 fn move_monsters_towards_player(
-    time: Res<Time>,
     player_query: Query<&Transform, (With<Player>, Without<Monster>)>,
-    mut monster_query: Query<(&mut Transform, &Monster), With<Monster>>,
+    mut monster_query: Query<(&Transform, &mut Velocity, &Stun, &Monster, &StatusEffects, Option<&RangedAttack>), Without<Charging>>,
 ) {
     if player_query.is_empty() {
         return;
@@ -84,78 +104,86 @@ fn move_monsters_towards_player(
 
     let player_pos = player_query.single().translation.truncate();
 
-    for (mut monster_transform, monster) in monster_query.iter_mut() {
+    for (monster_transform, mut velocity, stun, monster, effects, ranged) in monster_query.iter_mut() {
+        // Stunned monsters only drift with their knockback impulse
+        if stun.is_active() {
+            velocity.0 = Vec2::ZERO;
+            continue;
+        }
+
         let enemy_pos = monster_transform.translation.truncate();
-        let direction = player_pos - enemy_pos;
-        let distance = direction.length();
-        let direction = direction / distance;
+        let to_player = player_pos - enemy_pos;
+        let mut direction = to_player.normalize_or_zero();
+
+        // Ranged monsters keeping their distance hold position in range, and back off when too close
+        if let Some(ranged) = ranged.filter(|ranged| ranged.data.keep_distance) {
+            let distance = to_player.length();
+            if distance < ranged.data.range * RANGED_RETREAT_RATIO {
+                direction = -direction;
+            } else if distance < ranged.data.range * RANGED_HOLD_RATIO {
+                direction = Vec2::ZERO;
+            }
+        }
 
-        monster_transform.translation += direction.extend(0.0) * monster.speed * 100.0 * time.delta_seconds();
+        velocity.0 = direction * monster.speed * effects.speed_multiplier() * 100.0;
     }
 }
 
-fn spawn_monsters(
-    mut commands: Commands,
-    monster_manifest: Res<MonsterManifest>,
-    player_query: Query<&Transform, (With<Player>, Without<Monster>)>,
-    monster_query: Query<&Transform, With<Monster>>,
-) {
-    if player_query.is_empty() {
-        return;
+/// Spawns a monster from its manifest entry, scaled by the difficulty, returns its entity commands
+/// so that callers can insert extra components (e.g. bosses).
+pub(crate) fn spawn_monster<'a>(
+    commands: &'a mut Commands,
+    monster_id: Id<MonsterData>,
+    monster_data: &MonsterData,
+    position: Vec2,
+    scaling: &MonsterScaling,
+) -> EntityCommands<'a> {
+    let mut monster = commands
+        .spawn(MonsterBundle {
+            transform: Transform::from_translation(position.extend(MONSTER_Z_INDEX)).with_scale(Vec3::splat(3.0)),
+            aseprite: monster_data.sprite.clone(),
+            animation: AsepriteAnimation::from("walk"),
+            monster: Monster {
+                id: monster_id,
+                speed: monster_data.speed * scaling.speed,
+            },
+            vitals: VitalsBundle::new(monster_data.health * scaling.health, monster_data.defense),
+            contact_damage: ContactDamage::new(monster_data.attack * scaling.damage, monster_data.contact_cooldown),
+            motion: MotionBundle::default(),
+            mass: Mass(monster_data.mass),
+            status_effects: StatusEffects::default(),
+        });
+    monster.insert(GameEntity);
+
+    if let Some(mut ranged) = monster_data.ranged {
+        ranged.damage *= scaling.damage;
+        monster.insert(RangedAttack::new(ranged));
     }
 
-    let num_monsters = monster_query.iter().count();
-    if num_monsters >= MAX_NUM_MONSTERS {
-        return;
-    }
-    let spawn_count = (MAX_NUM_MONSTERS - num_monsters).min(MONSTER_SPAWN_RATE_PER_SECOND);
-    let player_pos = player_query.single().translation.truncate();
-
-    let mut rng = thread_rng();
-
-    let monster_ids = monster_manifest.0.keys().collect::<Vec<_>>();
-
-    for _ in 0..spawn_count {
-        let monster_id = *monster_ids.choose(&mut rng).unwrap();
-        let monster_data = monster_manifest.0.get(monster_id).unwrap();
-        let monster_pos = get_random_position_around(player_pos).extend(MONSTER_Z_INDEX);
-
-        commands
-            .spawn(MonsterBundle {
-                transform: Transform::from_translation(monster_pos).with_scale(Vec3::splat(3.0)),
-                aseprite: monster_data.sprite.clone(),
-                animation: AsepriteAnimation::from("walk"),
-                monster: Monster {
-                    id: *monster_id,
-                    speed: monster_data.speed,
-                },
-                health: Health(monster_data.health),
-            })
-            .insert(GameEntity);
-    }
-}
-
-fn get_random_position_around(pos: Vec2) -> Vec2 {
-    let mut rng = thread_rng();
-    let angle = rng.gen_range(0.0..PI * 2.0);
-    let dist = rng.gen_range(1000.0..5000.0);
-
-    let offset_x = angle.cos() * dist;
-    let offset_y = angle.sin() * dist;
-
-    let random_x = pos.x + offset_x;
-    let random_y = pos.y + offset_y;
-
-    vec2(random_x, random_y)
+    monster
 }
 
 #[derive(Debug, PartialEq, Component)]
 pub(crate) struct MonsterData {
     pub name: String,
     pub health: f32,
-    // pub attack: f32,
-    // pub defense: f32,
+    /// Damage dealt to the player on contact
+    pub attack: f32,
+    /// Flat reduction of every incoming hit
+    pub defense: f32,
+    /// Seconds between two contact hits on the player
+    pub contact_cooldown: f32,
     pub speed: f32,
+    /// Resistance to knockback
+    pub mass: f32,
+    pub ranged: Option<RangedAttackData>,
+    pub boss: Option<BossData>,
+    /// Value of the experience gem dropped on death
+    pub experience: f32,
+    /// Gold earned on death, banked into the profile at the end of the run
+    pub gold: u32,
+    /// Status effects applied to the player by the monster hits
+    pub on_hit: Vec<StatusEffectData>,
     pub sprite: Handle<Aseprite>,
 }
 
@@ -163,12 +191,38 @@ pub(crate) struct MonsterData {
 pub struct RawMonsterData {
     name: String,
     health: f32,
-    // attack: f32,
-    // defense: f32,
+    attack: f32,
+    defense: f32,
+    #[serde(default = "default_contact_cooldown")]
+    contact_cooldown: f32,
     speed: f32,
+    #[serde(default = "default_mass")]
+    mass: f32,
+    #[serde(default)]
+    ranged: Option<RangedAttackData>,
+    #[serde(default)]
+    boss: Option<BossData>,
+    #[serde(default = "default_experience")]
+    experience: f32,
+    #[serde(default)]
+    gold: u32,
+    #[serde(default)]
+    on_hit: Vec<StatusEffectData>,
     sprite: String,
 }
 
+fn default_mass() -> f32 {
+    1.0
+}
+
+fn default_contact_cooldown() -> f32 {
+    1.0
+}
+
+fn default_experience() -> f32 {
+    1.0
+}
+
 #[derive(Debug, Resource, PartialEq)]
 pub(crate) struct MonsterManifest(pub HashMap<Id<MonsterData>, MonsterData>);
 
@@ -199,9 +253,16 @@ impl Manifest for MonsterManifest {
                 let item = MonsterData {
                     name: raw_item.name,
                     health: raw_item.health,
-                    // attack: raw_item.attack,
-                    // defense: raw_item.defense,
+                    attack: raw_item.attack,
+                    defense: raw_item.defense,
+                    contact_cooldown: raw_item.contact_cooldown,
                     speed: raw_item.speed,
+                    mass: raw_item.mass,
+                    ranged: raw_item.ranged,
+                    boss: raw_item.boss,
+                    experience: raw_item.experience,
+                    gold: raw_item.gold,
+                    on_hit: raw_item.on_hit,
                     sprite: sprite_handle,
                 };
 
@@ -0,0 +1,91 @@
+use std::f32::consts::PI;
+
+use bevy::math::vec2;
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Distance kept between the visible area and the closest spawn point, so that monsters never
+/// pop in on screen
+pub const SPAWN_MARGIN: f32 = 100.0;
+/// Monsters spawn up to this distance beyond the margin, so that groups don't arrive in a single file
+pub const SPAWN_DEPTH: f32 = 150.0;
+/// Maximum angle between the player heading and a monster spawned `Ahead`
+pub const AHEAD_SPREAD: f32 = PI / 3.0;
+
+/// How a group of monsters is placed around the visible area.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SpawnPattern {
+    /// Random points all around the view
+    #[default]
+    Ring,
+    /// Evenly spread along the side of the view the player is heading to, or a random side
+    Line,
+    /// All around the view, but mostly in the direction the player is heading to
+    Ahead,
+}
+
+/// Positions of `count` monsters spawned just outside of `view`, the world rect seen by the camera.
+/// `heading` is the player normalized direction of travel, zero when standing still.
+pub(crate) fn spawn_positions(
+    pattern: SpawnPattern,
+    view: Rect,
+    heading: Vec2,
+    count: usize,
+    rng: &mut impl Rng,
+) -> Vec<Vec2> {
+    let area = view.inflate(SPAWN_MARGIN);
+
+    match pattern {
+        SpawnPattern::Ring => (0..count)
+            .map(|_| point_outside(area, Vec2::from_angle(rng.gen_range(0.0..PI * 2.0)), rng))
+            .collect(),
+        SpawnPattern::Ahead if heading != Vec2::ZERO => (0..count)
+            .map(|_| {
+                let angle = heading.y.atan2(heading.x) + rng.gen_range(-AHEAD_SPREAD..AHEAD_SPREAD);
+                point_outside(area, Vec2::from_angle(angle), rng)
+            })
+            .collect(),
+        SpawnPattern::Ahead => spawn_positions(SpawnPattern::Ring, view, heading, count, rng),
+        SpawnPattern::Line => {
+            let side = if heading != Vec2::ZERO {
+                heading
+            } else {
+                *[Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y].choose(rng).unwrap()
+            };
+            line_outside(area, side, count, rng)
+        }
+    }
+}
+
+/// Distance between `position` and the closest point of `view`, zero inside of it.
+pub(crate) fn distance_outside(view: Rect, position: Vec2) -> f32 {
+    ((position - view.center()).abs() - view.half_size()).max(Vec2::ZERO).length()
+}
+
+/// Point on the border of `area` in `direction` from its center, pushed up to `SPAWN_DEPTH` further.
+fn point_outside(area: Rect, direction: Vec2, rng: &mut impl Rng) -> Vec2 {
+    let half_size = area.half_size();
+    let scale_x = if direction.x != 0.0 { half_size.x / direction.x.abs() } else { f32::INFINITY };
+    let scale_y = if direction.y != 0.0 { half_size.y / direction.y.abs() } else { f32::INFINITY };
+
+    area.center() + direction * (scale_x.min(scale_y) + rng.gen_range(0.0..SPAWN_DEPTH))
+}
+
+/// Points evenly spread along the side of `area` facing `direction`.
+fn line_outside(area: Rect, direction: Vec2, count: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+    let (start, end, normal) = if direction.x.abs() >= direction.y.abs() {
+        let x = if direction.x > 0.0 { area.max.x } else { area.min.x };
+        (vec2(x, area.min.y), vec2(x, area.max.y), vec2(direction.x.signum(), 0.0))
+    } else {
+        let y = if direction.y > 0.0 { area.max.y } else { area.min.y };
+        (vec2(area.min.x, y), vec2(area.max.x, y), vec2(0.0, direction.y.signum()))
+    };
+
+    (0..count)
+        .map(|i| {
+            let t = (i as f32 + 0.5) / count as f32;
+            start.lerp(end, t) + normal * rng.gen_range(0.0..SPAWN_DEPTH)
+        })
+        .collect()
+}
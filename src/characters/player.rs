@@ -0,0 +1,442 @@
+use bevy::math::vec3;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use belly::prelude::*;
+use belly::widgets::common::Label;
+use bevy_aseprite::Aseprite;
+use bevy_aseprite::anim::AsepriteAnimation;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::{AnimationTimer, AtlasAnimation};
+use crate::characters::difficulty::{DifficultyManifest, SelectedDifficulty};
+use crate::common::motion::Stun;
+use crate::common::stats::{ModifierSource, Stat, Stats};
+use crate::common::status::StatusEffects;
+use crate::common::vitals::{Invulnerability, Regeneration, VitalsBundle};
+use crate::consts::*;
+use crate::experience::{Experience, Level};
+use crate::meta::achievements::{LockedContent, Unlock};
+use crate::meta::power_ups::{apply_power_ups, PowerUpManifest};
+use crate::meta::profile::Profile;
+use crate::player::{MoveDirection, Player, PlayerState};
+use crate::resources::GlobalTextureAtlas;
+use crate::state::GameState;
+use crate::upgrades::passives::{PassiveData, PassiveManifest, Passives};
+use crate::weapons::inventory::{equip_weapon, WeaponInventory};
+use crate::weapons::manifest::{WeaponData, WeaponManifest};
+use crate::world::GameEntity;
+
+pub(crate) struct CharacterPlugin;
+
+/// How the character is drawn.
+#[derive(Debug, PartialEq)]
+pub(crate) enum CharacterSprite {
+    /// Frames of the global texture atlas: 4 idle frames starting at `first`, then 4 run frames
+    Atlas { first: usize },
+    /// Aseprite file with `idle` and `run` tags
+    Aseprite(Handle<Aseprite>),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub enum RawCharacterSprite {
+    Atlas { first: usize },
+    Aseprite(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct CharacterData {
+    pub name: String,
+    pub description: String,
+    pub sprite: CharacterSprite,
+    /// Base stats overriding the default player ones
+    pub stats: HashMap<Stat, f32>,
+    pub starting_weapon: Id<WeaponData>,
+    /// Passive the character starts the run with, at level 1
+    pub perk: Option<Id<PassiveData>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawCharacterData {
+    name: String,
+    description: String,
+    sprite: RawCharacterSprite,
+    #[serde(default)]
+    stats: HashMap<Stat, f32>,
+    starting_weapon: String,
+    #[serde(default)]
+    perk: Option<String>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct CharacterManifest(pub HashMap<Id<CharacterData>, CharacterData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawCharacterManifest(Vec<RawCharacterData>);
+
+impl Manifest for CharacterManifest {
+    type RawManifest = RawCharacterManifest;
+    type RawItem = RawCharacterData;
+    type Item = CharacterData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        // Asset server to load our sprite assets
+        let asset_server = world.resource::<AssetServer>();
+
+        let characters: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                let sprite = match raw_item.sprite {
+                    RawCharacterSprite::Atlas { first } => CharacterSprite::Atlas { first },
+                    RawCharacterSprite::Aseprite(path) => CharacterSprite::Aseprite(asset_server.load(path)),
+                };
+
+                let item = CharacterData {
+                    name: raw_item.name,
+                    description: raw_item.description,
+                    sprite,
+                    stats: raw_item.stats,
+                    starting_weapon: Id::from_name(&raw_item.starting_weapon),
+                    perk: raw_item.perk.map(|perk| Id::from_name(&perk)),
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(CharacterManifest(characters))
+    }
+
+    fn get(&self, id: Id<CharacterData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
+
+impl CharacterManifest {
+    /// Characters in the order they are listed on the select screen
+    pub fn sorted(&self) -> Vec<(Id<CharacterData>, &CharacterData)> {
+        let mut characters = self.0.iter().map(|(id, character)| (*id, character)).collect::<Vec<_>>();
+        characters.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+        characters
+    }
+}
+
+/// Character played in the next run, picked on the character select screen.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct SelectedCharacter(pub Option<Id<CharacterData>>);
+
+/// Character highlighted on the select screen.
+#[derive(Resource, Default)]
+struct CharacterSelect {
+    selected: usize,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+enum CharacterSelectAction {
+    Previous,
+    Next,
+    /// Cycles through the difficulties
+    Difficulty,
+    Start,
+    Back,
+}
+
+#[derive(Component, Default)]
+struct CharacterListLabel;
+
+#[derive(Component, Default)]
+struct DifficultyLabel;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<CharacterManifest>("characters/data.ron")
+            .init_resource::<SelectedCharacter>()
+            .init_resource::<CharacterSelect>()
+            .add_event::<CharacterSelectAction>()
+            .add_systems(OnEnter(GameState::CharacterSelect), spawn_character_select_screen)
+            .add_systems(OnExit(GameState::CharacterSelect), despawn_character_select_screen)
+            .add_systems(OnEnter(GameState::GameInit), spawn_player)
+            .add_systems(
+                Update,
+                (
+                    handle_character_select_keyboard,
+                    handle_character_select_actions,
+                    update_character_select_screen,
+                ).chain().run_if(in_state(GameState::CharacterSelect)),
+            );
+    }
+}
+
+/// Default player stats, for the ones a character does not override.
+fn default_player_stats() -> [(Stat, f32); 9] {
+    [
+        (Stat::MoveSpeed, PLAYER_SPEED),
+        (Stat::MaxHealth, PLAYER_MAX_HEALTH),
+        (Stat::Armor, PLAYER_ARMOR),
+        (Stat::Regeneration, 0.0),
+        (Stat::PickupRadius, PLAYER_PICKUP_RADIUS),
+        (Stat::Damage, 1.0),
+        (Stat::Cooldown, 1.0),
+        (Stat::ProjectileSpeed, 1.0),
+        (Stat::Amount, 0.0),
+    ]
+}
+
+fn spawn_player(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    selected: Res<SelectedCharacter>,
+    character_manifest: Res<CharacterManifest>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+    power_up_manifest: Res<PowerUpManifest>,
+    profile: Res<Profile>,
+    locked: Res<LockedContent>,
+) {
+    // Runs started without going through the select screen use the first unlocked character
+    let character = selected.0
+        .and_then(|id| character_manifest.0.get(&id))
+        .or_else(|| {
+            character_manifest.sorted()
+                .into_iter()
+                .map(|(_, character)| character)
+                .find(|character| !locked.is_locked(&Unlock::Character(character.name.clone())))
+        })
+        .expect("no unlocked character in the character manifest");
+
+    let weapon = weapon_manifest.0
+        .get(&character.starting_weapon)
+        .expect("starting weapon is missing from the weapon manifest");
+    let mut inventory = WeaponInventory::default();
+    equip_weapon(&mut commands, &mut inventory, character.starting_weapon, weapon, &handle);
+
+    let mut stats = Stats::new(default_player_stats().map(|(stat, value)| {
+        (stat, character.stats.get(&stat).copied().unwrap_or(value))
+    }));
+    apply_power_ups(&mut stats, &profile, &power_up_manifest);
+
+    let mut passives = Passives::default();
+    if let Some((perk, passive)) = character.perk.and_then(|id| passive_manifest.0.get(&id).map(|passive| (id, passive))) {
+        passives.0.insert(perk, 1);
+        stats.add_modifiers(ModifierSource::Passive(perk), &passive.modifiers);
+    }
+
+    let transform = Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)).with_translation(vec3(0.0, 0.0, PLAYER_Z_INDEX));
+    let mut player = commands.spawn((
+        Player,
+        PlayerState::default(),
+        MoveDirection::default(),
+        VitalsBundle::new(stats.get(Stat::MaxHealth), stats.get(Stat::Armor)),
+        Invulnerability::new(PLAYER_INVULNERABILITY_WINDOW),
+        Regeneration(stats.get(Stat::Regeneration)),
+        StatusEffects::default(),
+        Stun::default(),
+        Level::default(),
+        Experience::default(),
+        stats,
+        inventory,
+        passives,
+        GameEntity,
+    ));
+
+    match &character.sprite {
+        CharacterSprite::Atlas { first } => {
+            player.insert((
+                SpriteSheetBundle {
+                    texture: handle.image.clone().unwrap(),
+                    atlas: TextureAtlas {
+                        layout: handle.layout.clone().unwrap(),
+                        index: *first,
+                    },
+                    transform,
+                    ..default()
+                },
+                AtlasAnimation { first: *first },
+                AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+            ));
+        }
+        CharacterSprite::Aseprite(sprite) => {
+            player.insert((
+                sprite.clone(),
+                AsepriteAnimation::from("idle"),
+                transform,
+            ));
+        }
+    }
+}
+
+fn spawn_character_select_screen(
+    mut commands: Commands,
+    mut select: ResMut<CharacterSelect>,
+    character_manifest: Res<CharacterManifest>,
+    locked: Res<LockedContent>,
+) {
+    // Highlight the first playable character
+    select.selected = character_manifest.sorted()
+        .iter()
+        .position(|(_, character)| !locked.is_locked(&Unlock::Character(character.name.clone())))
+        .unwrap_or(0);
+
+    let list = commands.spawn_empty().id();
+    let difficulty = commands.spawn_empty().id();
+    commands.add(eml! {
+        <div c:menu id="characterselect">
+            <label value="Choose a character" c:title/>
+            <label {list} with=CharacterListLabel c:characters-list/>
+            <label {difficulty} with=DifficultyLabel c:characters-difficulty/>
+            <div c:characters-actions>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(CharacterSelectAction::Previous); });
+                })>
+                    <label value="Previous [W]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(CharacterSelectAction::Next); });
+                })>
+                    <label value="Next [S]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(CharacterSelectAction::Difficulty); });
+                })>
+                    <label value="Difficulty [Tab]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(CharacterSelectAction::Start); });
+                })>
+                    <label value="Start [Enter]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(CharacterSelectAction::Back); });
+                })>
+                    <label value="Back [Backspace]"/>
+                </button>
+            </div>
+        </div>
+    });
+}
+
+fn despawn_character_select_screen(
+    mut elements: Elements,
+) {
+    elements.select("#characterselect").remove();
+}
+
+fn handle_character_select_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut ew: EventWriter<CharacterSelectAction>,
+) {
+    if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        ew.send(CharacterSelectAction::Previous);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        ew.send(CharacterSelectAction::Next);
+    }
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        ew.send(CharacterSelectAction::Difficulty);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+        ew.send(CharacterSelectAction::Start);
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        ew.send(CharacterSelectAction::Back);
+    }
+}
+
+fn handle_character_select_actions(
+    mut events: EventReader<CharacterSelectAction>,
+    mut select: ResMut<CharacterSelect>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut difficulty: ResMut<SelectedDifficulty>,
+    mut next_state: ResMut<NextState<GameState>>,
+    character_manifest: Res<CharacterManifest>,
+    difficulty_manifest: Res<DifficultyManifest>,
+    locked: Res<LockedContent>,
+) {
+    let characters = character_manifest.sorted();
+    let count = characters.len();
+
+    for action in events.read() {
+        match *action {
+            CharacterSelectAction::Previous if count > 0 => select.selected = (select.selected + count - 1) % count,
+            CharacterSelectAction::Next if count > 0 => select.selected = (select.selected + 1) % count,
+            CharacterSelectAction::Difficulty => {
+                let difficulties = &difficulty_manifest.0;
+                let next = difficulties
+                    .iter()
+                    .position(|data| Id::from_name(&data.name) == difficulty.0)
+                    .map_or(0, |current| (current + 1) % difficulties.len());
+                if let Some(data) = difficulties.get(next) {
+                    difficulty.0 = Id::from_name(&data.name);
+                }
+            }
+            CharacterSelectAction::Start => {
+                let Some((id, character)) = characters.get(select.selected) else {
+                    continue;
+                };
+                if locked.is_locked(&Unlock::Character(character.name.clone())) {
+                    continue;
+                }
+                selected.0 = Some(*id);
+                next_state.set(GameState::GameInit);
+            }
+            CharacterSelectAction::Back => next_state.set(GameState::MainMenu),
+            _ => {}
+        }
+    }
+}
+
+fn update_character_select_screen(
+    select: Res<CharacterSelect>,
+    character_manifest: Res<CharacterManifest>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+    locked: Res<LockedContent>,
+    difficulty: Res<SelectedDifficulty>,
+    difficulty_manifest: Res<DifficultyManifest>,
+    mut list_query: Query<&mut Label, (With<CharacterListLabel>, Without<DifficultyLabel>)>,
+    mut difficulty_query: Query<&mut Label, (With<DifficultyLabel>, Without<CharacterListLabel>)>,
+) {
+    if !select.is_changed() && !difficulty.is_changed() {
+        return;
+    }
+
+    if let Ok(mut label) = difficulty_query.get_single_mut() {
+        label.value = difficulty_manifest
+            .get(difficulty.0)
+            .map_or(String::from("Difficulty: ?"), |data| format!("Difficulty: {} - {}", data.name, data.description));
+    }
+
+    let Ok(mut label) = list_query.get_single_mut() else {
+        return;
+    };
+    label.value = character_manifest.sorted()
+        .iter()
+        .enumerate()
+        .map(|(i, (_, character))| {
+            let marker = if i == select.selected { ">" } else { " " };
+            if locked.is_locked(&Unlock::Character(character.name.clone())) {
+                return format!("{} {} - Locked", marker, character.name);
+            }
+            let weapon = weapon_manifest.0
+                .get(&character.starting_weapon)
+                .map_or("?", |weapon| weapon.name.as_str());
+            let perk = character.perk
+                .and_then(|perk| passive_manifest.0.get(&perk))
+                .map_or(String::new(), |passive| format!(", starts with {}", passive.name));
+            format!("{} {} - {} ({}{})", marker, character.name, character.description, weapon, perk)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
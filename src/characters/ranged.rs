@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::monsters::{Monster, MonsterManifest};
+use crate::common::status::StatusEffectData;
+use crate::consts::*;
+use crate::player::Player;
+use crate::resources::GlobalTextureAtlas;
+use crate::state::GameState;
+use crate::world::{GameEntity, RunTimer};
+
+pub(crate) struct RangedPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RangedAttackData {
+    /// Index of the projectile sprite in the global texture atlas
+    pub projectile_sprite_index: usize,
+    /// Shots per second
+    pub fire_rate: f32,
+    /// Distance under which the monster starts shooting at the player
+    pub range: f32,
+    pub projectile_speed: f32,
+    pub damage: f32,
+    /// Whether the monster stops approaching (and backs off) once the player is in range
+    pub keep_distance: bool,
+}
+
+#[derive(Debug, Component, Clone)]
+pub(crate) struct RangedAttack {
+    pub data: RangedAttackData,
+    /// Elapsed game time at which the monster can shoot again
+    pub ready_at: f32,
+}
+
+impl RangedAttack {
+    pub fn new(data: RangedAttackData) -> Self {
+        Self { data, ready_at: 0.0 }
+    }
+}
+
+/// Projectile fired by a monster, only collides with the player.
+#[derive(Debug, Component)]
+pub(crate) struct MonsterProjectile {
+    pub damage: f32,
+    pub velocity: Vec2,
+    /// `RunTimer` time at which the projectile despawns
+    pub expires_at: f32,
+    pub source: Entity,
+    /// Status effects applied to the player on hit
+    pub on_hit: Vec<StatusEffectData>,
+}
+
+impl Plugin for RangedPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    fire_monster_projectiles,
+                    update_monster_projectiles,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn fire_monster_projectiles(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    handle: Res<GlobalTextureAtlas>,
+    monster_manifest: Res<MonsterManifest>,
+    player_query: Query<&Transform, With<Player>>,
+    mut monster_query: Query<(&Transform, &mut RangedAttack, &Monster, Entity), Without<Player>>,
+) {
+    if player_query.is_empty() || monster_query.is_empty() {
+        return;
+    }
+
+    let now = run_timer.elapsed_secs();
+    let player_pos = player_query.single().translation.truncate();
+
+    for (transform, mut ranged, monster, entity) in monster_query.iter_mut() {
+        if now < ranged.ready_at {
+            continue;
+        }
+
+        let monster_pos = transform.translation.truncate();
+        let to_player = player_pos - monster_pos;
+        if to_player.length_squared() > ranged.data.range * ranged.data.range {
+            continue;
+        }
+        ranged.ready_at = now + 1.0 / ranged.data.fire_rate.max(0.01);
+
+        let direction = to_player.normalize_or_zero();
+        spawn_monster_projectile(
+            &mut commands,
+            &handle,
+            ranged.data.projectile_sprite_index,
+            monster_pos,
+            MonsterProjectile {
+                damage: ranged.data.damage,
+                velocity: direction * ranged.data.projectile_speed,
+                expires_at: now + ranged.data.range * 1.5 / ranged.data.projectile_speed.max(1.0),
+                source: entity,
+                on_hit: monster_manifest.0
+                    .get(&monster.id)
+                    .map(|monster_data| monster_data.on_hit.clone())
+                    .unwrap_or_default(),
+            },
+        );
+    }
+}
+
+pub(crate) fn spawn_monster_projectile(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    sprite_index: usize,
+    position: Vec2,
+    projectile: MonsterProjectile,
+) {
+    commands.spawn((
+        SpriteSheetBundle {
+            texture: handle.image.clone().unwrap(),
+            atlas: TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: sprite_index,
+            },
+            sprite: Sprite {
+                color: MONSTER_PROJECTILE_COLOR,
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(BULLET_Z_INDEX))
+                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+            ..default()
+        },
+        projectile,
+        GameEntity,
+    ));
+}
+
+fn update_monster_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    run_timer: Res<RunTimer>,
+    mut projectile_query: Query<(&mut Transform, &MonsterProjectile, Entity)>,
+) {
+    let now = run_timer.elapsed_secs();
+
+    for (mut transform, projectile, entity) in projectile_query.iter_mut() {
+        if now >= projectile.expires_at {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
+    }
+}
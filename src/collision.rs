@@ -4,17 +4,23 @@ use bevy::time::common_conditions::on_timer;
 use kd_tree::{KdPoint, KdTree};
 
 use crate::consts::*;
-use crate::gun::Bullet;
-use crate::characters::monsters::Monster;
-use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::common::damage::{roll_crit, DamageEvent, DamageKind};
+use crate::common::motion::KnockbackEvent;
+use crate::common::status::ApplyStatusEvent;
+use crate::gun::{Bullet, BulletDirection};
+use crate::characters::monsters::{ContactDamage, Monster, MonsterManifest};
+use crate::characters::ranged::MonsterProjectile;
+use crate::player::Player;
+use crate::rng::RunRng;
 use crate::state::GameState;
+use crate::weapons::projectile::{Bounce, ExplosionEvent, Explosive, HitList, OnHitEffects, Pierce};
 
 pub struct CollisionPlugin;
 
 #[derive(Component)]
-struct Collidable {
-    pos: Vec2,
-    entity: Entity,
+pub(crate) struct Collidable {
+    pub pos: Vec2,
+    pub entity: Entity,
 }
 
 impl KdPoint for Collidable {
@@ -30,7 +36,7 @@ impl KdPoint for Collidable {
 }
 
 #[derive(Resource)]
-struct EnemyKdTree(KdTree<Collidable>);
+pub(crate) struct EnemyKdTree(pub KdTree<Collidable>);
 
 impl Default for EnemyKdTree {
     fn default() -> Self {
@@ -46,7 +52,9 @@ impl Plugin for CollisionPlugin {
                 Update,
                 (
                     handle_enemy_player_collision,
+                    handle_monster_projectile_player_collision,
                     handle_enemy_bullet_collision,
+                    handle_explosions,
                     update_enemy_kd_tree.run_if(on_timer(Duration::from_secs_f32(KD_TREE_REFRESH_RATE))),
                 )
                     .run_if(in_state(GameState::InGame)),
@@ -71,45 +79,217 @@ fn update_enemy_kd_tree(
 
 fn handle_enemy_bullet_collision(
     mut commands: Commands,
-    bullet_query: Query<(&Transform, Entity), With<Bullet>>,
+    mut bullet_query: Query<(
+        &Transform,
+        &Bullet,
+        &mut BulletDirection,
+        &mut HitList,
+        Option<&mut Pierce>,
+        Option<&mut Bounce>,
+        Option<&Explosive>,
+        Option<&OnHitEffects>,
+        Entity,
+    )>,
     tree: Res<EnemyKdTree>,
-    mut enemy_query: Query<(&Transform, &mut Monster), With<Monster>>,
+    enemy_query: Query<(), With<Monster>>,
+    mut ew: EventWriter<DamageEvent>,
+    mut knockback_ew: EventWriter<KnockbackEvent>,
+    mut explosion_ew: EventWriter<ExplosionEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
+    mut run_rng: ResMut<RunRng>,
 ) {
     if bullet_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
-    for (bullet_transform, entity) in bullet_query.iter() {
-        let pos = bullet_transform.translation;
+    let rng = &mut run_rng.combat;
+
+    for (bullet_transform, bullet, mut direction, mut hit_list, pierce, bounce, explosive, on_hit, bullet_entity) in bullet_query.iter_mut() {
+        let pos = bullet_transform.translation.truncate();
         let enemies = tree.0.within_radius(&[pos.x, pos.y], 25.0);
 
-        for e in enemies {
-            if let Ok((_, mut enemy)) = enemy_query.get_mut(e.entity) {
-                if let Some(mut entity_command) = commands.get_entity(entity) {
-                    // enemy.health -= BULLET_DAMAGE;
-                    entity_command.despawn();
+        // A bullet only hits the first living monster it touches, and never the same one twice
+        let Some(e) = enemies
+            .iter()
+            .find(|e| enemy_query.contains(e.entity) && !hit_list.0.contains(&e.entity)) else {
+            continue;
+        };
+
+        let (amount, crit) = roll_crit(bullet.damage, bullet.crit_chance, bullet.crit_multiplier, rng);
+        ew.send(DamageEvent {
+            target: e.entity,
+            amount,
+            source: bullet_entity,
+            kind: bullet.kind,
+            crit,
+            over_time: false,
+        });
+        knockback_ew.send(KnockbackEvent {
+            target: e.entity,
+            direction: direction.0,
+            force: bullet.knockback,
+        });
+        hit_list.0.push(e.entity);
+
+        if let Some(on_hit) = on_hit {
+            for effect in on_hit.0.iter() {
+                status_ew.send(ApplyStatusEvent {
+                    target: e.entity,
+                    effect: *effect,
+                    source: bullet_entity,
+                });
+            }
+        }
+
+        if let Some(explosive) = explosive {
+            explosion_ew.send(ExplosionEvent {
+                position: pos,
+                radius: explosive.0.radius,
+                damage: explosive.0.damage,
+                knockback: bullet.knockback,
+                source: bullet_entity,
+            });
+        }
+
+        if let Some(mut bounce) = bounce {
+            if bounce.0 > 0 {
+                let next_target = tree.0
+                    .nearests(&[pos.x, pos.y], BOUNCE_SEARCH_COUNT)
+                    .into_iter()
+                    .filter(|n| n.squared_distance <= BOUNCE_RANGE * BOUNCE_RANGE)
+                    .find(|n| enemy_query.contains(n.item.entity) && !hit_list.0.contains(&n.item.entity));
+
+                if let Some(next_target) = next_target {
+                    bounce.0 -= 1;
+                    direction.0 = (next_target.item.pos - pos).normalize_or_zero();
+                    continue;
                 }
             }
         }
+
+        if let Some(mut pierce) = pierce {
+            if pierce.0 > 0 {
+                pierce.0 -= 1;
+                continue;
+            }
+        }
+
+        commands.entity(bullet_entity).despawn();
+    }
+}
+
+fn handle_explosions(
+    mut events: EventReader<ExplosionEvent>,
+    tree: Res<EnemyKdTree>,
+    enemy_query: Query<(), With<Monster>>,
+    mut ew: EventWriter<DamageEvent>,
+    mut knockback_ew: EventWriter<KnockbackEvent>,
+) {
+    for explosion in events.read() {
+        let pos = explosion.position;
+        for e in tree.0.within_radius(&[pos.x, pos.y], explosion.radius) {
+            if enemy_query.contains(e.entity) {
+                ew.send(DamageEvent {
+                    target: e.entity,
+                    amount: explosion.damage,
+                    source: explosion.source,
+                    kind: DamageKind::Explosion,
+                    crit: false,
+                    over_time: false,
+                });
+                knockback_ew.send(KnockbackEvent {
+                    target: e.entity,
+                    direction: e.pos - pos,
+                    force: explosion.knockback,
+                });
+            }
+        }
     }
 }
 
 fn handle_enemy_player_collision(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+    player_query: Query<(&Transform, Entity), With<Player>>,
     tree: Res<EnemyKdTree>,
-    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
+    monster_manifest: Res<MonsterManifest>,
+    mut enemy_query: Query<(&mut ContactDamage, &Monster)>,
+    mut ew: EventWriter<DamageEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
-    let player_pos = player_query.single().translation;
+    let now = time.elapsed_seconds();
+    let (player_transform, player) = player_query.single();
+    let player_pos = player_transform.translation;
     let enemies = tree.0.within_radius(&[player_pos.x, player_pos.y], 50.0);
     for c in enemies.iter() {
-        if let Some(mut entity_command) = commands.get_entity(c.entity) {
-            ew.send(PlayerEnemyCollisionEvent);
-            entity_command.despawn();
+        let Ok((mut contact_damage, monster)) = enemy_query.get_mut(c.entity) else {
+            continue;
+        };
+
+        if now < contact_damage.ready_at {
+            continue;
+        }
+        contact_damage.ready_at = now + contact_damage.cooldown;
+
+        ew.send(DamageEvent {
+            target: player,
+            amount: contact_damage.amount,
+            source: c.entity,
+            kind: DamageKind::Physical,
+            crit: false,
+            over_time: false,
+        });
+
+        if let Some(monster_data) = monster_manifest.0.get(&monster.id) {
+            for effect in monster_data.on_hit.iter() {
+                status_ew.send(ApplyStatusEvent {
+                    target: player,
+                    effect: *effect,
+                    source: c.entity,
+                });
+            }
+        }
+    }
+}
+
+fn handle_monster_projectile_player_collision(
+    mut commands: Commands,
+    player_query: Query<(&Transform, Entity), With<Player>>,
+    projectile_query: Query<(&Transform, &MonsterProjectile, Entity)>,
+    mut ew: EventWriter<DamageEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
+) {
+    if player_query.is_empty() || projectile_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+
+    for (transform, projectile, entity) in projectile_query.iter() {
+        let pos = transform.translation.truncate();
+        if pos.distance_squared(player_pos) > MONSTER_PROJECTILE_HIT_RADIUS * MONSTER_PROJECTILE_HIT_RADIUS {
+            continue;
+        }
+
+        ew.send(DamageEvent {
+            target: player,
+            amount: projectile.damage,
+            source: projectile.source,
+            kind: DamageKind::Physical,
+            crit: false,
+            over_time: false,
+        });
+        for effect in projectile.on_hit.iter() {
+            status_ew.send(ApplyStatusEvent {
+                target: player,
+                effect: *effect,
+                source: projectile.source,
+            });
         }
+        commands.entity(entity).despawn();
     }
 }
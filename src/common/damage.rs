@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use leafwing_manifest::identifier::Id;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::monsters::{Monster, MonsterData};
+use crate::common::vitals::{Armor, Health, Invulnerability};
+use crate::state::GameState;
+
+pub(crate) struct DamagePlugin;
+
+/// Request to deal `amount` damage to `target`. Every damage source (bullets, contact, ...) goes
+/// through this event so that other systems can react to hits.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub source: Entity,
+    pub kind: DamageKind,
+    pub crit: bool,
+    /// Damage over time tick from a status effect, it neither respects nor triggers invulnerability
+    pub over_time: bool,
+}
+
+/// Sent for every hit that actually went through, with the damage left after mitigation.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct DamageDealt {
+    pub target: Entity,
+    pub amount: f32,
+    pub source: Entity,
+    pub position: Vec2,
+    pub kind: DamageKind,
+    pub crit: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum DamageKind {
+    #[default]
+    Physical,
+    Fire,
+    Poison,
+    Explosion,
+}
+
+/// Rolls a critical hit, returns the damage to deal and whether it was a crit.
+pub(crate) fn roll_crit(damage: f32, crit_chance: f32, crit_multiplier: f32, rng: &mut impl Rng) -> (f32, bool) {
+    if crit_chance > 0.0 && rng.gen::<f32>() < crit_chance {
+        (damage * crit_multiplier, true)
+    } else {
+        (damage, false)
+    }
+}
+
+/// Sent once when a monster's health drops to zero. The monster is despawned right after.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct MonsterKilled {
+    pub entity: Entity,
+    pub id: Id<MonsterData>,
+    pub position: Vec2,
+    pub source: Entity,
+}
+
+/// Order of the damage pipeline in `PostUpdate`. Systems reacting to `MonsterKilled` that still
+/// need to query the dead monster go in `DamageSet::Killed`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum DamageSet {
+    Apply,
+    Killed,
+    Despawn,
+}
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<DamageEvent>()
+            .add_event::<DamageDealt>()
+            .add_event::<MonsterKilled>()
+            .configure_sets(
+                PostUpdate,
+                (
+                    DamageSet::Apply,
+                    DamageSet::Killed,
+                    DamageSet::Despawn,
+                ).chain().run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(PostUpdate, apply_damage.in_set(DamageSet::Apply))
+            .add_systems(PostUpdate, despawn_killed_monsters.in_set(DamageSet::Despawn));
+    }
+}
+
+fn apply_damage(
+    mut events: EventReader<DamageEvent>,
+    mut target_query: Query<(&mut Health, &Transform, Option<&Armor>, Option<&mut Invulnerability>, Option<&Monster>)>,
+    mut ew: EventWriter<MonsterKilled>,
+    mut dealt_ew: EventWriter<DamageDealt>,
+) {
+    for event in events.read() {
+        let Ok((mut health, transform, armor, invulnerability, monster)) = target_query.get_mut(event.target) else {
+            continue;
+        };
+
+        // Already dead, waiting to be despawned
+        if health.is_dead() {
+            continue;
+        }
+
+        if let Some(mut invulnerability) = invulnerability.filter(|_| !event.over_time) {
+            if invulnerability.is_active() {
+                continue;
+            }
+            invulnerability.trigger();
+        }
+
+        let amount = match armor {
+            Some(armor) => armor.mitigate(event.amount),
+            None => event.amount,
+        };
+        health.current -= amount;
+
+        dealt_ew.send(DamageDealt {
+            target: event.target,
+            amount,
+            source: event.source,
+            position: transform.translation.truncate(),
+            kind: event.kind,
+            crit: event.crit,
+        });
+
+        if health.is_dead() {
+            if let Some(monster) = monster {
+                ew.send(MonsterKilled {
+                    entity: event.target,
+                    id: monster.id,
+                    position: transform.translation.truncate(),
+                    source: event.source,
+                });
+            }
+        }
+    }
+}
+
+fn despawn_killed_monsters(
+    mut commands: Commands,
+    mut events: EventReader<MonsterKilled>,
+) {
+    for event in events.read() {
+        if let Some(entity_command) = commands.get_entity(event.entity) {
+            entity_command.despawn_recursive();
+        }
+    }
+}
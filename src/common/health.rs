@@ -1,4 +0,0 @@
-use bevy::prelude::Component;
-
-#[derive(Component, Debug, Clone, Copy)]
-pub struct Health(pub f32);
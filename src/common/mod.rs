@@ -1 +1,5 @@
-pub(crate) mod health;
+pub(crate) mod vitals;
+pub(crate) mod damage;
+pub(crate) mod motion;
+pub(crate) mod status;
+pub(crate) mod stats;
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::consts::*;
+use crate::state::GameState;
+
+pub(crate) struct MotionPlugin;
+
+/// Velocity the entity moves at on its own (e.g. a monster chasing the player).
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub(crate) struct Velocity(pub Vec2);
+
+/// Velocity coming from external pushes such as knockback. It is added on top of `Velocity`
+/// and decays over time.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub(crate) struct Impulse(pub Vec2);
+
+/// Resistance to knockback, the pushed distance is divided by the mass.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Mass(pub f32);
+
+/// While active, the entity does not move on its own (only its `Impulse` applies).
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub(crate) struct Stun {
+    pub remaining: f32,
+}
+
+impl Stun {
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    pub fn apply(&mut self, duration: f32) {
+        self.remaining = self.remaining.max(duration);
+    }
+}
+
+#[derive(Bundle, Debug, Default)]
+pub(crate) struct MotionBundle {
+    pub velocity: Velocity,
+    pub impulse: Impulse,
+    pub stun: Stun,
+}
+
+/// Pushes `target` in `direction`, the resulting speed is `force` divided by the target `Mass`.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct KnockbackEvent {
+    pub target: Entity,
+    pub direction: Vec2,
+    pub force: f32,
+}
+
+impl Plugin for MotionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<KnockbackEvent>()
+            .add_systems(
+                PostUpdate,
+                (
+                    apply_knockback,
+                    tick_stun,
+                    apply_motion,
+                ).chain().run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn apply_knockback(
+    mut events: EventReader<KnockbackEvent>,
+    mut query: Query<(&mut Impulse, &mut Stun, Option<&Mass>)>,
+) {
+    for event in events.read() {
+        if event.force <= 0.0 {
+            continue;
+        }
+
+        let Ok((mut impulse, mut stun, mass)) = query.get_mut(event.target) else {
+            continue;
+        };
+
+        let mass = mass.map_or(1.0, |mass| mass.0.max(0.1));
+        impulse.0 += event.direction.normalize_or_zero() * event.force / mass;
+        stun.apply(HIT_STUN_DURATION);
+    }
+}
+
+fn tick_stun(
+    time: Res<Time>,
+    mut query: Query<&mut Stun>,
+) {
+    for mut stun in query.iter_mut() {
+        if stun.is_active() {
+            stun.remaining -= time.delta_seconds();
+        }
+    }
+}
+
+fn apply_motion(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &Velocity, &mut Impulse)>,
+) {
+    let dt = time.delta_seconds();
+    let damping = (-IMPULSE_DAMPING * dt).exp();
+
+    for (mut transform, velocity, mut impulse) in query.iter_mut() {
+        transform.translation += ((velocity.0 + impulse.0) * dt).extend(0.0);
+
+        if impulse.0 != Vec2::ZERO {
+            impulse.0 *= damping;
+            if impulse.0.length_squared() < 1.0 {
+                impulse.0 = Vec2::ZERO;
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_manifest::identifier::Id;
+use serde::{Deserialize, Serialize};
+
+use crate::common::vitals::{Armor, Health, Regeneration};
+use crate::meta::power_ups::PowerUpData;
+use crate::state::GameState;
+use crate::upgrades::passives::PassiveData;
+
+pub(crate) struct StatsPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Stat {
+    /// Units per second
+    MoveSpeed,
+    MaxHealth,
+    Armor,
+    /// Health regenerated per second
+    Regeneration,
+    /// Distance under which experience gems fly to the player
+    PickupRadius,
+    /// Multiplier of the damage dealt by every weapon
+    Damage,
+    /// Multiplier of every weapon cooldown
+    Cooldown,
+    /// Multiplier of the projectiles speed
+    ProjectileSpeed,
+    /// Extra projectiles fired per shot
+    Amount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Modifier {
+    /// Added to the base value
+    Add(f32),
+    /// Fraction of the value added on top of it, `-0.08` meaning -8%
+    Percent(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StatModifier {
+    pub stat: Stat,
+    pub modifier: Modifier,
+}
+
+/// What granted a modifier, so that it can be removed later on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ModifierSource {
+    Passive(Id<PassiveData>),
+    /// Bought in the power ups shop, applied at the start of the run
+    PowerUp(Id<PowerUpData>),
+}
+
+/// Base values plus modifier stacks. Derived values are cached and recomputed whenever the
+/// modifiers change: `(base + sum of Add) * product of (1 + Percent)`.
+#[derive(Component, Debug, Default)]
+pub(crate) struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<(ModifierSource, StatModifier)>,
+    values: HashMap<Stat, f32>,
+}
+
+impl Stats {
+    pub fn new(base: impl IntoIterator<Item = (Stat, f32)>) -> Self {
+        let mut stats = Self {
+            base: base.into_iter().collect(),
+            ..default()
+        };
+        stats.recompute();
+        stats
+    }
+
+    /// Final value of the stat, 0.0 when the stat has no base value
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.values.get(&stat).copied().unwrap_or(0.0)
+    }
+
+    pub fn add_modifiers(&mut self, source: ModifierSource, modifiers: &[StatModifier]) {
+        self.modifiers.extend(modifiers.iter().map(|modifier| (source, *modifier)));
+        self.recompute();
+    }
+
+    pub fn remove_modifiers(&mut self, source: ModifierSource) {
+        self.modifiers.retain(|(modifier_source, _)| *modifier_source != source);
+        self.recompute();
+    }
+
+    fn recompute(&mut self) {
+        self.values = self.base.clone();
+
+        for (stat, value) in self.values.iter_mut() {
+            let mut flat = 0.0;
+            let mut factor = 1.0;
+            for (_, modifier) in self.modifiers.iter().filter(|(_, modifier)| modifier.stat == *stat) {
+                match modifier.modifier {
+                    Modifier::Add(amount) => flat += amount,
+                    Modifier::Percent(fraction) => factor *= 1.0 + fraction,
+                }
+            }
+
+            *value = ((*value + flat) * factor).max(0.0);
+        }
+    }
+}
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                sync_vitals_with_stats.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Vitals are shared with monsters, which have no stats, so the player ones are kept in sync.
+fn sync_vitals_with_stats(
+    mut query: Query<(&Stats, &mut Health, &mut Armor, &mut Regeneration), Changed<Stats>>,
+) {
+    for (stats, mut health, mut armor, mut regeneration) in query.iter_mut() {
+        let max_health = stats.get(Stat::MaxHealth);
+        if max_health != health.max {
+            let gained = max_health - health.max;
+            health.max = max_health;
+            if gained > 0.0 {
+                health.heal(gained);
+            } else {
+                health.current = health.current.min(max_health);
+            }
+        }
+
+        armor.0 = stats.get(Stat::Armor);
+        regeneration.0 = stats.get(Stat::Regeneration);
+    }
+}
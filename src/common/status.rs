@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::elites::EliteTint;
+use crate::common::damage::{DamageEvent, DamageKind};
+use crate::common::motion::Stun;
+use crate::consts::*;
+use crate::rng::RunRng;
+use crate::state::GameState;
+
+pub(crate) struct StatusPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum StatusKind {
+    /// Damage over time, refreshed on re-application
+    Burn,
+    /// Damage over time, each application adds a stack
+    Poison,
+    /// Reduces movement speed by `magnitude` (0.0 to 1.0)
+    Slow,
+    /// Prevents any movement
+    Freeze,
+    /// Stops the target from moving on its own, through the same `motion::Stun` as hit-stun
+    Stun,
+}
+
+/// How re-applying an effect already active on a target behaves.
+enum Stacking {
+    /// Keeps a single instance, resetting its duration and keeping the strongest magnitude
+    Refresh,
+    /// Adds a new instance, up to `max` instances at once
+    Stack { max: usize },
+}
+
+impl StatusKind {
+    fn stacking(&self) -> Stacking {
+        match self {
+            StatusKind::Poison => Stacking::Stack { max: MAX_POISON_STACKS },
+            _ => Stacking::Refresh,
+        }
+    }
+
+    fn tint(&self) -> Color {
+        match self {
+            StatusKind::Burn => Color::rgb(1.0, 0.5, 0.2),
+            StatusKind::Poison => Color::rgb(0.5, 1.0, 0.3),
+            StatusKind::Slow => Color::rgb(0.6, 0.6, 1.0),
+            StatusKind::Freeze => Color::rgb(0.4, 0.9, 1.0),
+            StatusKind::Stun => Color::rgb(1.0, 1.0, 0.4),
+        }
+    }
+
+    fn damage_kind(&self) -> DamageKind {
+        match self {
+            StatusKind::Burn => DamageKind::Fire,
+            StatusKind::Poison => DamageKind::Poison,
+            _ => DamageKind::Physical,
+        }
+    }
+
+    /// Higher priority effects decide the sprite tint
+    fn tint_priority(&self) -> u8 {
+        match self {
+            StatusKind::Freeze => 4,
+            StatusKind::Stun => 3,
+            StatusKind::Burn => 2,
+            StatusKind::Poison => 1,
+            StatusKind::Slow => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StatusEffectData {
+    pub kind: StatusKind,
+    /// Seconds the effect lasts
+    pub duration: f32,
+    /// Damage per second for burn and poison, speed reduction for slow
+    #[serde(default)]
+    pub magnitude: f32,
+    /// Chance (0.0 to 1.0) of the effect being applied on hit
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ActiveStatus {
+    pub kind: StatusKind,
+    pub magnitude: f32,
+    pub remaining: f32,
+    /// Seconds until the next damage tick
+    pub next_tick: f32,
+    pub source: Entity,
+}
+
+#[derive(Component, Debug, Default)]
+pub(crate) struct StatusEffects(pub Vec<ActiveStatus>);
+
+impl StatusEffects {
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|status| status.kind == kind)
+    }
+
+    /// Multiplier to apply to the entity movement speed
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.has(StatusKind::Freeze) {
+            return 0.0;
+        }
+
+        let slow = self.0
+            .iter()
+            .filter(|status| status.kind == StatusKind::Slow)
+            .map(|status| status.magnitude)
+            .fold(0.0, f32::max);
+
+        (1.0 - slow).clamp(0.0, 1.0)
+    }
+
+    fn tint(&self) -> Option<Color> {
+        self.0
+            .iter()
+            .max_by_key(|status| status.kind.tint_priority())
+            .map(|status| status.kind.tint())
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct ApplyStatusEvent {
+    pub target: Entity,
+    pub effect: StatusEffectData,
+    pub source: Entity,
+}
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<ApplyStatusEvent>()
+            .add_systems(
+                Update,
+                (
+                    apply_status_effects,
+                    tick_status_effects,
+                    update_sprite_tint,
+                ).chain().run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn apply_status_effects(
+    mut events: EventReader<ApplyStatusEvent>,
+    mut query: Query<(&mut StatusEffects, Option<&mut Stun>)>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let rng = &mut run_rng.combat;
+
+    for event in events.read() {
+        let Ok((mut effects, stun)) = query.get_mut(event.target) else {
+            continue;
+        };
+        if event.effect.chance < 1.0 && !rng.gen_bool(event.effect.chance.max(0.0) as f64) {
+            continue;
+        }
+
+        // Movement is stopped by the `Stun` shared with hit-stun, the active status only drives the tint
+        if event.effect.kind == StatusKind::Stun {
+            if let Some(mut stun) = stun {
+                stun.apply(event.effect.duration);
+            }
+        }
+
+        let status = ActiveStatus {
+            kind: event.effect.kind,
+            magnitude: event.effect.magnitude,
+            remaining: event.effect.duration,
+            next_tick: STATUS_TICK_INTERVAL,
+            source: event.source,
+        };
+
+        match event.effect.kind.stacking() {
+            Stacking::Refresh => {
+                if let Some(active) = effects.0.iter_mut().find(|active| active.kind == status.kind) {
+                    active.remaining = active.remaining.max(status.remaining);
+                    active.magnitude = active.magnitude.max(status.magnitude);
+                    active.source = status.source;
+                } else {
+                    effects.0.push(status);
+                }
+            }
+            Stacking::Stack { max } => {
+                let stacks = effects.0.iter().filter(|active| active.kind == status.kind).count();
+                if stacks >= max {
+                    // Replace the stack closest to expiring
+                    if let Some(oldest) = effects.0
+                        .iter_mut()
+                        .filter(|active| active.kind == status.kind)
+                        .min_by(|a, b| a.remaining.total_cmp(&b.remaining)) {
+                        *oldest = status;
+                    }
+                } else {
+                    effects.0.push(status);
+                }
+            }
+        }
+    }
+}
+
+fn tick_status_effects(
+    time: Res<Time>,
+    mut query: Query<(&mut StatusEffects, Entity)>,
+    mut ew: EventWriter<DamageEvent>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut effects, entity) in query.iter_mut() {
+        // Avoid flagging every idle monster as changed
+        if effects.0.is_empty() {
+            continue;
+        }
+
+        for status in effects.0.iter_mut() {
+            status.remaining -= dt;
+
+            if matches!(status.kind, StatusKind::Burn | StatusKind::Poison) {
+                status.next_tick -= dt;
+                if status.next_tick <= 0.0 {
+                    status.next_tick += STATUS_TICK_INTERVAL;
+                    ew.send(DamageEvent {
+                        target: entity,
+                        amount: status.magnitude * STATUS_TICK_INTERVAL,
+                        source: status.source,
+                        kind: status.kind.damage_kind(),
+                        crit: false,
+                        over_time: true,
+                    });
+                }
+            }
+        }
+
+        effects.0.retain(|status| status.remaining > 0.0);
+    }
+}
+
+/// The sprite may be (re)created by the aseprite plugin once loaded, so the tint is reapplied
+/// whenever either the sprite or the effects change.
+fn update_sprite_tint(
+    mut query: Query<
+        (&mut Sprite, &StatusEffects, Option<&EliteTint>),
+        Or<(Changed<Sprite>, Changed<StatusEffects>)>,
+    >,
+) {
+    for (mut sprite, effects, elite_tint) in query.iter_mut() {
+        let color = effects
+            .tint()
+            .or(elite_tint.map(|tint| tint.0))
+            .unwrap_or(Color::WHITE);
+
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
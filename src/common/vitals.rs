@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::state::GameState;
+
+pub(crate) struct VitalsPlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+}
+
+/// Health regenerated per second.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Regeneration(pub f32);
+
+/// Flat damage reduction applied to every incoming hit.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Armor(pub f32);
+
+impl Armor {
+    pub fn mitigate(&self, amount: f32) -> f32 {
+        (amount - self.0).max(0.0)
+    }
+}
+
+/// Window after a hit during which the entity ignores any further damage.
+#[derive(Component, Debug, Clone)]
+pub(crate) struct Invulnerability {
+    pub window: f32,
+    pub remaining: f32,
+}
+
+impl Invulnerability {
+    pub fn new(window: f32) -> Self {
+        Self { window, remaining: 0.0 }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    pub fn trigger(&mut self) {
+        self.remaining = self.window;
+    }
+}
+
+#[derive(Bundle, Debug)]
+pub(crate) struct VitalsBundle {
+    pub health: Health,
+    pub armor: Armor,
+}
+
+impl VitalsBundle {
+    pub fn new(max_health: f32, armor: f32) -> Self {
+        Self {
+            health: Health::new(max_health),
+            armor: Armor(armor),
+        }
+    }
+}
+
+impl Plugin for VitalsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    regenerate_health,
+                    tick_invulnerability,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn regenerate_health(
+    time: Res<Time>,
+    mut query: Query<(&mut Health, &Regeneration)>,
+) {
+    for (mut health, regeneration) in query.iter_mut() {
+        if health.is_dead() || health.current >= health.max {
+            continue;
+        }
+
+        health.heal(regeneration.0 * time.delta_seconds());
+    }
+}
+
+fn tick_invulnerability(
+    time: Res<Time>,
+    mut query: Query<&mut Invulnerability>,
+) {
+    for mut invulnerability in query.iter_mut() {
+        if invulnerability.is_active() {
+            invulnerability.remaining -= time.delta_seconds();
+        }
+    }
+}
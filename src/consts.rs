@@ -1,3 +1,5 @@
+use bevy::prelude::Color;
+
 pub const WW: f32 = 1200.0;
 pub const WH: f32 = 900.0;
 pub const BG_COLOR: (u8, u8, u8) = (25, 20, 43);
@@ -15,12 +17,18 @@ pub const BULLET_Z_INDEX: f32 = 1.0;
 pub const WORLD_DECORATION_Z_INDEX: f32 = 0.0;
 pub const ENEMY_Z_INDEX: f32 = 9.0;
 
-pub const BULLET_SPAWN_INTERVAL: f32 = 0.25;
-pub const BULLET_SPEED: f32 = 700.0;
-pub const BULLET_DAMAGE: f32 = 15.0;
-pub const BULLET_LIFETIME: f32 = 0.5;
-pub const NUM_BULLETS_PER_SHOT: usize = 5;
-pub const BULLET_MAX_SPREADING_ANGLE: f32 = 0.15;
+pub const MAX_WEAPON_SLOTS: usize = 6;
+pub const WEAPON_MOUNT_RADIUS: f32 = 30.0;
+pub const AUTO_TARGET_RANGE: f32 = 600.0;
+pub const BOUNCE_RANGE: f32 = 400.0;
+pub const BOUNCE_SEARCH_COUNT: usize = 8;
+pub const AURA_Z_INDEX: f32 = 0.5;
+pub const AURA_ALPHA: f32 = 0.25;
+pub const ORBIT_BLADE_HIT_RADIUS: f32 = 30.0;
+pub const MELEE_EFFECT_DURATION: f32 = 0.15;
+/// Damage added by each weapon level, as a fraction of the base damage
+pub const WEAPON_LEVEL_DAMAGE_BONUS: f32 = 0.25;
+pub const MAX_PASSIVE_SLOTS: usize = 6;
 
 pub const WORLD_W: f32 = 3000.0;
 pub const WORLD_H: f32 = 3000.0;
@@ -29,12 +37,28 @@ pub const NUM_WORLD_DECORATIONS: usize = 500;
 
 pub const PLAYER_SPEED: f32 = 300.0;
 pub const PLAYER_MAX_HEALTH: f32 = 10.0;
+pub const PLAYER_ARMOR: f32 = 0.0;
+pub const PLAYER_INVULNERABILITY_WINDOW: f32 = 0.5;
+pub const PLAYER_PICKUP_RADIUS: f32 = 120.0;
 
 pub const MAX_NUM_ENEMIES: usize = 10000;
 pub const ENEMY_SPAWN_INTERVAL: f32 = 1.0;
 pub const ENEMY_SPAWN_RATE_PER_SECOND: usize = 2;
 pub const ENEMY_SPEED: f32 = 225.0;
 pub const ENEMY_MAX_HEALTH: f32 = 100.0;
-pub const ENEMY_DAMAGE: f32 = 1.0;
 
 pub const KD_TREE_REFRESH_RATE: f32 = 0.1;
+
+pub const HIT_STUN_DURATION: f32 = 0.15;
+pub const IMPULSE_DAMPING: f32 = 8.0;
+
+pub const MONSTER_PROJECTILE_HIT_RADIUS: f32 = 25.0;
+pub const MONSTER_PROJECTILE_COLOR: Color = Color::rgb(1.0, 0.35, 0.35);
+
+pub const BOSS_SPAWN_DISTANCE: f32 = 800.0;
+pub const BOSS_SUMMON_RADIUS: f32 = 120.0;
+pub const BOSS_PROJECTILE_LIFETIME: f32 = 4.0;
+
+/// Seconds between two damage ticks of burn and poison
+pub const STATUS_TICK_INTERVAL: f32 = 0.5;
+pub const MAX_POISON_STACKS: usize = 5;
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::Rng;
+
+use crate::common::damage::{DamageDealt, DamageKind};
+use crate::player::Player;
+use crate::settings::damage_numbers_enabled;
+use crate::state::GameState;
+use crate::world::GameEntity;
+
+/// Number of text entities reused for damage numbers, the oldest one is recycled when all are in use
+pub const DAMAGE_NUMBER_POOL_SIZE: usize = 200;
+pub const DAMAGE_NUMBER_LIFETIME: f32 = 0.7;
+pub const DAMAGE_NUMBER_RISE_SPEED: f32 = 80.0;
+pub const DAMAGE_NUMBER_FONT_SIZE: f32 = 32.0;
+pub const DAMAGE_NUMBER_CRIT_SCALE: f32 = 1.6;
+/// Maximum horizontal offset from the hit position, so stacked numbers stay readable
+pub const DAMAGE_NUMBER_JITTER: f32 = 15.0;
+pub const DAMAGE_NUMBER_Z_INDEX: f32 = 20.0;
+
+pub(crate) struct DamageNumbersPlugin;
+
+#[derive(Resource, Default)]
+struct DamageNumberFont(Handle<Font>);
+
+/// Text entities spawned once per run, used as a ring buffer.
+#[derive(Resource, Default)]
+struct DamageNumberPool {
+    entities: Vec<Entity>,
+    next: usize,
+}
+
+#[derive(Component, Default)]
+struct DamageNumber {
+    /// Seconds before the number is hidden again
+    remaining: f32,
+}
+
+impl Plugin for DamageNumbersPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DamageNumberFont>()
+            .init_resource::<DamageNumberPool>()
+            .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(OnEnter(GameState::GameInit), spawn_damage_number_pool)
+            .add_systems(
+                Update,
+                (
+                    show_damage_numbers.run_if(damage_numbers_enabled),
+                    update_damage_numbers,
+                ).chain().run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn load_assets(
+    mut font: ResMut<DamageNumberFont>,
+    asset_server: Res<AssetServer>,
+) {
+    font.0 = asset_server.load("fonts/monogram.ttf");
+}
+
+fn spawn_damage_number_pool(
+    mut commands: Commands,
+    font: Res<DamageNumberFont>,
+    mut pool: ResMut<DamageNumberPool>,
+) {
+    pool.next = 0;
+    pool.entities = (0..DAMAGE_NUMBER_POOL_SIZE)
+        .map(|_| {
+            commands
+                .spawn((
+                    Text2dBundle {
+                        text: Text::from_section(
+                            "",
+                            TextStyle {
+                                font: font.0.clone(),
+                                font_size: DAMAGE_NUMBER_FONT_SIZE,
+                                color: Color::WHITE,
+                            },
+                        ).with_justify(JustifyText::Center),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    DamageNumber::default(),
+                    GameEntity,
+                ))
+                .id()
+        })
+        .collect();
+}
+
+fn damage_color(kind: DamageKind) -> Color {
+    match kind {
+        DamageKind::Physical => Color::WHITE,
+        DamageKind::Fire => Color::rgb(1.0, 0.55, 0.2),
+        DamageKind::Poison => Color::rgb(0.5, 1.0, 0.3),
+        DamageKind::Explosion => Color::rgb(1.0, 0.85, 0.3),
+    }
+}
+
+/// Hits on the same target during a frame are summed into a single number.
+fn show_damage_numbers(
+    mut events: EventReader<DamageDealt>,
+    mut pool: ResMut<DamageNumberPool>,
+    player_query: Query<(), With<Player>>,
+    mut number_query: Query<(&mut Text, &mut Transform, &mut Visibility, &mut DamageNumber)>,
+) {
+    if pool.entities.is_empty() {
+        return;
+    }
+
+    let mut hits: HashMap<Entity, DamageDealt> = HashMap::new();
+    for event in events.read() {
+        hits.entry(event.target)
+            .and_modify(|hit| {
+                hit.amount += event.amount;
+                hit.crit |= event.crit;
+                hit.position = event.position;
+            })
+            .or_insert(*event);
+    }
+
+    let mut rng = rand::thread_rng();
+
+    for (target, hit) in hits {
+        let entity = pool.entities[pool.next];
+        pool.next = (pool.next + 1) % pool.entities.len();
+
+        let Ok((mut text, mut transform, mut visibility, mut number)) = number_query.get_mut(entity) else {
+            continue;
+        };
+
+        let color = if player_query.contains(target) {
+            Color::RED
+        } else {
+            damage_color(hit.kind)
+        };
+        let section = &mut text.sections[0];
+        section.value = if hit.amount < 1.0 {
+            format!("{:.1}", hit.amount)
+        } else {
+            format!("{:.0}", hit.amount)
+        };
+        if hit.crit {
+            section.value.push('!');
+        }
+        section.style.color = color;
+
+        let jitter = rng.gen_range(-DAMAGE_NUMBER_JITTER..DAMAGE_NUMBER_JITTER);
+        let scale = if hit.crit { DAMAGE_NUMBER_CRIT_SCALE } else { 1.0 };
+        *transform = Transform::from_xyz(hit.position.x + jitter, hit.position.y, DAMAGE_NUMBER_Z_INDEX)
+            .with_scale(Vec3::splat(scale));
+        *visibility = Visibility::Visible;
+        number.remaining = DAMAGE_NUMBER_LIFETIME;
+    }
+}
+
+fn update_damage_numbers(
+    time: Res<Time>,
+    mut number_query: Query<(&mut Text, &mut Transform, &mut Visibility, &mut DamageNumber)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut text, mut transform, mut visibility, mut number) in number_query.iter_mut() {
+        // Idle pool entries are left untouched
+        if number.remaining <= 0.0 {
+            continue;
+        }
+
+        number.remaining -= dt;
+        if number.remaining <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_NUMBER_RISE_SPEED * dt;
+        text.sections[0].style.color.set_a(number.remaining / DAMAGE_NUMBER_LIFETIME);
+    }
+}
@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use crate::characters::monsters::MonsterManifest;
+use crate::common::damage::{DamageSet, MonsterKilled};
+use crate::common::stats::{Stat, Stats};
+use crate::player::Player;
+use crate::state::GameState;
+use crate::world::GameEntity;
+
+pub const XP_GEM_SIZE: f32 = 10.0;
+pub const XP_GEM_COLOR: Color = Color::rgb(0.3, 0.8, 1.0);
+pub const XP_GEM_Z_INDEX: f32 = 2.0;
+/// Speed of a gem pulled towards the player
+pub const XP_GEM_SPEED: f32 = 700.0;
+/// Distance under which a pulled gem is collected
+pub const XP_GEM_COLLECT_RADIUS: f32 = 20.0;
+/// Experience required to reach level 2
+pub const XP_CURVE_BASE: f32 = 5.0;
+/// Multiplier applied to the required experience at each level
+pub const XP_CURVE_GROWTH: f32 = 1.25;
+
+pub(crate) struct ExperiencePlugin;
+
+#[derive(Component)]
+pub(crate) struct XpGem {
+    pub value: f32,
+    /// Whether the gem entered the player pickup radius, it keeps flying to the player afterwards
+    pub attracted: bool,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Level(pub u32);
+
+impl Default for Level {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// Experience collected towards the next level.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub(crate) struct Experience(pub f32);
+
+/// Experience required to go from a level to the next one.
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct ExperienceCurve {
+    pub base: f32,
+    pub growth: f32,
+}
+
+impl Default for ExperienceCurve {
+    fn default() -> Self {
+        Self {
+            base: XP_CURVE_BASE,
+            growth: XP_CURVE_GROWTH,
+        }
+    }
+}
+
+impl ExperienceCurve {
+    pub fn required(&self, level: u32) -> f32 {
+        self.base * self.growth.powi(level.saturating_sub(1) as i32)
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct PlayerLevelUp {
+    pub level: u32,
+}
+
+/// Level ups not handled yet, the game stays in `GameState::LevelUp` until it reaches zero.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct PendingLevelUps(pub u32);
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ExperienceCurve>()
+            .init_resource::<PendingLevelUps>()
+            .add_event::<PlayerLevelUp>()
+            .add_systems(OnEnter(GameState::GameInit), reset_pending_level_ups)
+            .add_systems(OnEnter(GameState::LevelUp), pause_game_time)
+            .add_systems(OnExit(GameState::LevelUp), resume_game_time)
+            .add_systems(PostUpdate, spawn_xp_gems.in_set(DamageSet::Killed))
+            .add_systems(
+                Update,
+                (
+                    collect_xp_gems,
+                    check_player_level_up,
+                    enter_level_up,
+                ).chain().run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn reset_pending_level_ups(mut pending: ResMut<PendingLevelUps>) {
+    pending.0 = 0;
+}
+
+/// Everything relying on the game clock (cooldowns, timers, projectile lifetimes) stops while
+/// the player picks an upgrade.
+fn pause_game_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_game_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn spawn_xp_gems(
+    mut commands: Commands,
+    mut events: EventReader<MonsterKilled>,
+    monster_manifest: Res<MonsterManifest>,
+) {
+    for event in events.read() {
+        let Some(monster_data) = monster_manifest.0.get(&event.id) else {
+            continue;
+        };
+        if monster_data.experience <= 0.0 {
+            continue;
+        }
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: XP_GEM_COLOR,
+                    custom_size: Some(Vec2::splat(XP_GEM_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(event.position.extend(XP_GEM_Z_INDEX))
+                    .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+                ..default()
+            },
+            XpGem {
+                value: monster_data.experience,
+                attracted: false,
+            },
+            GameEntity,
+        ));
+    }
+}
+
+fn collect_xp_gems(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(&Transform, &Stats, &mut Experience), With<Player>>,
+    mut gem_query: Query<(&mut Transform, &mut XpGem, Entity), Without<Player>>,
+) {
+    if player_query.is_empty() || gem_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, stats, mut experience) = player_query.single_mut();
+    let pickup_radius = stats.get(Stat::PickupRadius);
+    let player_pos = player_transform.translation.truncate();
+    let step = XP_GEM_SPEED * time.delta_seconds();
+
+    for (mut transform, mut gem, entity) in gem_query.iter_mut() {
+        let to_player = player_pos - transform.translation.truncate();
+        let distance = to_player.length();
+
+        if !gem.attracted {
+            if distance > pickup_radius {
+                continue;
+            }
+            gem.attracted = true;
+        }
+
+        if distance <= XP_GEM_COLLECT_RADIUS.max(step) {
+            experience.0 += gem.value;
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += (to_player / distance * step).extend(0.0);
+    }
+}
+
+fn check_player_level_up(
+    curve: Res<ExperienceCurve>,
+    mut player_query: Query<(&mut Experience, &mut Level), (With<Player>, Changed<Experience>)>,
+    mut ew: EventWriter<PlayerLevelUp>,
+) {
+    for (mut experience, mut level) in player_query.iter_mut() {
+        while curve.required(level.0) > 0.0 && experience.0 >= curve.required(level.0) {
+            experience.0 -= curve.required(level.0);
+            level.0 += 1;
+            ew.send(PlayerLevelUp { level: level.0 });
+        }
+    }
+}
+
+fn enter_level_up(
+    mut events: EventReader<PlayerLevelUp>,
+    mut pending: ResMut<PendingLevelUps>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    pending.0 += events.read().count() as u32;
+    if pending.0 > 0 {
+        next_state.set(GameState::LevelUp);
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use belly::prelude::*;
+use leafwing_manifest::manifest::Manifest;
+
+use crate::characters::difficulty::{DifficultyManifest, SelectedDifficulty};
+use crate::experience::Level;
+use crate::meta::gold::RunGold;
+use crate::player::Player;
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::world::RunTimer;
+
+pub struct GameOverPlugin;
+
+#[derive(Event, Debug, Clone, Copy)]
+enum GameOverAction {
+    MainMenu,
+}
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<GameOverAction>()
+            .add_systems(OnEnter(GameState::GameOver), spawn_gameover_screen)
+            .add_systems(OnExit(GameState::GameOver), despawn_gameover_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_gameover_keyboard,
+                    handle_gameover_actions,
+                ).chain().run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+/// Summary of the run that just ended, with the seed to replay it with `--seed`.
+fn spawn_gameover_screen(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    run_gold: Res<RunGold>,
+    run_rng: Res<RunRng>,
+    difficulty: Res<SelectedDifficulty>,
+    difficulty_manifest: Res<DifficultyManifest>,
+    player_query: Query<&Level, With<Player>>,
+) {
+    let seconds = run_timer.elapsed_secs() as u32;
+    let survived = format!("Survived {:02}:{:02}", seconds / 60, seconds % 60);
+    let level = format!("Level {}", player_query.get_single().map_or(1, |level| level.0));
+    let gold = format!("Gold earned {}", run_gold.0);
+    let seed = format!("Seed {}", run_rng.seed);
+    let difficulty = format!("Difficulty {}", difficulty_manifest.get(difficulty.0).map_or("?", |data| data.name.as_str()));
+
+    commands.add(eml! {
+        <div c:menu id="gameover">
+            <label value="Game Over" c:title/>
+            <label value=survived/>
+            <label value=level/>
+            <label value=gold/>
+            <label value=difficulty/>
+            <label value=seed/>
+            <button on:press=run!(|ctx| {
+                ctx.add(|world: &mut World| { world.send_event(GameOverAction::MainMenu); });
+            })>
+                <label value="Main Menu [Enter]"/>
+            </button>
+        </div>
+    });
+}
+
+fn despawn_gameover_screen(
+    mut elements: Elements,
+) {
+    elements.select("#gameover").remove();
+}
+
+fn handle_gameover_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut ew: EventWriter<GameOverAction>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        ew.send(GameOverAction::MainMenu);
+    }
+}
+
+fn handle_gameover_actions(
+    mut events: EventReader<GameOverAction>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for action in events.read() {
+        match action {
+            GameOverAction::MainMenu => next_state.set(GameState::MainMenu),
+        }
+    }
+}
@@ -1,15 +1,23 @@
 use std::f32::consts::PI;
-use std::time::Instant;
 
+use bevy::ecs::system::EntityCommands;
 use bevy::math::{vec2, vec3};
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
+use leafwing_manifest::plugin::RegisterManifest;
 use rand::Rng;
 
+use crate::collision::EnemyKdTree;
+use crate::common::damage::DamageKind;
+use crate::common::stats::{Stat, Stats};
 use crate::consts::*;
 use crate::player::Player;
+use crate::rng::RunRng;
 use crate::state::GameState;
 use crate::resources::{CursorPosition, GlobalTextureAtlas};
+use crate::weapons::inventory::{find_aim_target, Weapon, WeaponMount};
+use crate::weapons::manifest::{Targeting, WeaponData, WeaponManifest};
+use crate::weapons::projectile::{Bounce, ExplosionEvent, Explosive, HitList, Homing, OnHitEffects, Pierce};
 use crate::world::GameEntity;
 
 pub struct GunPlugin;
@@ -21,148 +29,236 @@ pub struct Gun;
 pub struct GunTimer(pub Stopwatch);
 
 #[derive(Component)]
-pub struct Bullet;
+pub struct Bullet {
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    pub knockback: f32,
+    pub speed: f32,
+    pub lifetime: f32,
+}
 
 #[derive(Component)]
-struct BulletDirection(Vec2);
+pub(crate) struct BulletDirection(pub Vec2);
 
+/// Elapsed game time at which the bullet was fired.
 #[derive(Component)]
-pub struct SpawnInstant(Instant);
+pub struct SpawnInstant(f32);
 
 impl Plugin for GunPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                update_gun_transform,
-                update_bullets,
-                despawn_old_bullets,
-                handle_gun_input,
-                flip_gun_sprite_y,
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app
+            .register_manifest::<WeaponManifest>("weapons/data.ron")
+            .add_systems(
+                Update,
+                (
+                    update_gun_transform,
+                    update_bullets,
+                    despawn_old_bullets,
+                    handle_gun_input,
+                    flip_gun_sprite_y,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
+pub(crate) fn insert_gun(entity: &mut EntityCommands, weapon: &WeaponData, handle: &GlobalTextureAtlas) {
+    entity.insert((
+        SpriteSheetBundle {
+            texture: handle.image.clone().unwrap(),
+            atlas: TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: weapon.sprite_index,
+            },
+            transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)).with_translation(vec3(0.0, 0.0, GUN_Z_INDEX)),
+            ..default()
+        },
+        Gun,
+    ));
+}
+
 fn update_gun_transform(
     cursor_pos: Res<CursorPosition>,
+    tree: Res<EnemyKdTree>,
     player_query: Query<&Transform, With<Player>>,
-    mut gun_query: Query<&mut Transform, (With<Gun>, Without<Player>)>,
+    mut gun_query: Query<(&mut Transform, &WeaponMount, &Targeting), (With<Gun>, Without<Player>)>,
 ) {
     if player_query.is_empty() || gun_query.is_empty() {
         return;
     }
 
     let player_pos = player_query.single().translation.truncate();
-    let cursor_pos = match cursor_pos.0 {
-        Some(pos) => pos,
-        None => player_pos,
-    };
-    let mut gun_transform = gun_query.single_mut();
-
-    let angle = (player_pos.y - cursor_pos.y).atan2(player_pos.x - cursor_pos.x) + PI;
-    gun_transform.rotation = Quat::from_rotation_z(angle);
-
-    let offset = 20.0;
-    let new_gun_pos = vec2(
-        player_pos.x + offset * angle.cos() - 5.0,
-        player_pos.y + offset * angle.sin() - 10.0,
-    );
-
-    gun_transform.translation = vec3(new_gun_pos.x, new_gun_pos.y, gun_transform.translation.z);
-    gun_transform.translation.z = 15.0;
+
+    for (mut gun_transform, mount, targeting) in gun_query.iter_mut() {
+        let pivot = player_pos + mount.0;
+        let target = find_aim_target(*targeting, pivot, cursor_pos.0, &tree)
+            .or(cursor_pos.0)
+            .unwrap_or(pivot);
+
+        let angle = (pivot.y - target.y).atan2(pivot.x - target.x) + PI;
+        gun_transform.rotation = Quat::from_rotation_z(angle);
+
+        let offset = 20.0;
+        let new_gun_pos = vec2(
+            pivot.x + offset * angle.cos() - 5.0,
+            pivot.y + offset * angle.sin() - 10.0,
+        );
+
+        gun_transform.translation = vec3(new_gun_pos.x, new_gun_pos.y, 15.0);
+    }
 }
 
 fn handle_gun_input(
     mut commands: Commands,
     time: Res<Time>,
-    mut gun_query: Query<(&Transform, &mut GunTimer), With<Gun>>,
+    cursor_pos: Res<CursorPosition>,
+    tree: Res<EnemyKdTree>,
+    player_query: Query<(&Transform, &Stats), (With<Player>, Without<Gun>)>,
+    mut gun_query: Query<(&Transform, &mut GunTimer, &Weapon, &WeaponMount, &Targeting), With<Gun>>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     handle: Res<GlobalTextureAtlas>,
+    weapon_manifest: Res<WeaponManifest>,
+    mut run_rng: ResMut<RunRng>,
 ) {
-    if gun_query.is_empty() {
+    if player_query.is_empty() || gun_query.is_empty() {
         return;
     }
 
-    let (gun_transform, mut gun_timer) = gun_query.single_mut();
-    let gun_pos = gun_transform.translation.truncate();
-    gun_timer.0.tick(time.delta());
+    let (player_transform, stats) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+    let rng = &mut run_rng.spread;
 
-    if !mouse_button_input.pressed(MouseButton::Left) || gun_timer.0.elapsed_secs() < BULLET_SPAWN_INTERVAL {
-        return;
-    }
+    for (gun_transform, mut gun_timer, gun, mount, targeting) in gun_query.iter_mut() {
+        let Some(weapon) = weapon_manifest.0.get(&gun.id) else {
+            continue;
+        };
+        gun_timer.0.tick(time.delta());
 
-    gun_timer.0.reset();
-    let mut rng = rand::thread_rng();
+        if gun_timer.0.elapsed_secs() < weapon.cooldown * stats.get(Stat::Cooldown) {
+            continue;
+        }
 
-    let gun_direction = gun_transform.rotation.mul_vec3(Vec3::X).truncate();
+        // Cursor weapons fire on click, the others fire on their own as soon as they have a target
+        let wants_to_fire = match targeting {
+            Targeting::Cursor => mouse_button_input.pressed(MouseButton::Left),
+            _ => find_aim_target(*targeting, player_pos + mount.0, cursor_pos.0, &tree).is_some(),
+        };
+        if !wants_to_fire {
+            continue;
+        }
 
-    for _ in 0..NUM_BULLETS_PER_SHOT {
-        let bullet_spread_angle = rng.gen_range(-BULLET_MAX_SPREADING_ANGLE..BULLET_MAX_SPREADING_ANGLE);
-        let bullet_direction = vec2(
-            gun_direction.x * bullet_spread_angle.cos() - gun_direction.y * bullet_spread_angle.sin(),
-            gun_direction.x * bullet_spread_angle.sin() + gun_direction.y * bullet_spread_angle.cos(),
-        );
+        gun_timer.0.reset();
+
+        let gun_pos = gun_transform.translation.truncate();
+        let gun_direction = gun_transform.rotation.mul_vec3(Vec3::X).truncate();
+
+        let projectiles = weapon.projectiles_per_shot + stats.get(Stat::Amount).round() as usize;
+        for _ in 0..projectiles {
+            let bullet_spread_angle = if weapon.spread_angle > 0.0 {
+                rng.gen_range(-weapon.spread_angle..weapon.spread_angle)
+            } else {
+                0.0
+            };
+            let bullet_direction = vec2(
+                gun_direction.x * bullet_spread_angle.cos() - gun_direction.y * bullet_spread_angle.sin(),
+                gun_direction.x * bullet_spread_angle.sin() + gun_direction.y * bullet_spread_angle.cos(),
+            );
 
-        commands.spawn((
-            SpriteSheetBundle {
-                texture: handle.image.clone().unwrap(),
-                atlas: TextureAtlas {
-                    layout: handle.layout.clone().unwrap(),
-                    index: 16,
+            let mut bullet = commands.spawn((
+                SpriteSheetBundle {
+                    texture: handle.image.clone().unwrap(),
+                    atlas: TextureAtlas {
+                        layout: handle.layout.clone().unwrap(),
+                        index: weapon.projectile_sprite_index,
+                    },
+                    transform: Transform::from_translation(Vec3::new(gun_pos.x, gun_pos.y, BULLET_Z_INDEX))
+                        .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                    ..default()
                 },
-                transform: Transform::from_translation(Vec3::new(gun_pos.x, gun_pos.y, BULLET_Z_INDEX))
-                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-                ..default()
-            },
-            Bullet,
-            BulletDirection(bullet_direction.normalize()),
-            GameEntity,
-            SpawnInstant(Instant::now()),
-        ));
+                Bullet {
+                    damage: weapon.damage_at(gun.level) * stats.get(Stat::Damage),
+                    kind: weapon.damage_kind,
+                    crit_chance: weapon.crit_chance,
+                    crit_multiplier: weapon.crit_multiplier,
+                    knockback: weapon.knockback,
+                    speed: weapon.projectile_speed,
+                    lifetime: weapon.projectile_lifetime,
+                },
+                BulletDirection(bullet_direction.normalize()),
+                HitList::default(),
+                GameEntity,
+                SpawnInstant(time.elapsed_seconds()),
+            ));
+
+            if weapon.pierce > 0 {
+                bullet.insert(Pierce(weapon.pierce));
+            }
+            if weapon.bounce > 0 {
+                bullet.insert(Bounce(weapon.bounce));
+            }
+            if let Some(homing) = weapon.homing {
+                bullet.insert(Homing(homing));
+            }
+            if let Some(mut explosion) = weapon.explosion_at(gun.level) {
+                explosion.damage *= stats.get(Stat::Damage);
+                bullet.insert(Explosive(explosion));
+            }
+            if !weapon.on_hit.is_empty() {
+                bullet.insert(OnHitEffects(weapon.on_hit.clone()));
+            }
+        }
     }
 }
 
 fn update_bullets(
     time: Res<Time>,
-    mut bullet_query: Query<(&mut Transform, &BulletDirection), With<Bullet>>,
+    player_query: Query<&Stats, With<Player>>,
+    mut bullet_query: Query<(&mut Transform, &BulletDirection, &Bullet)>,
 ) {
-    if bullet_query.is_empty() {
+    if player_query.is_empty() || bullet_query.is_empty() {
         return;
     }
 
-    for (mut t, dir) in bullet_query.iter_mut() {
-        t.translation += dir.0.normalize().extend(0.0) * Vec3::splat(BULLET_SPEED) * time.delta_seconds();
+    let speed_multiplier = player_query.single().get(Stat::ProjectileSpeed);
+    for (mut t, dir, bullet) in bullet_query.iter_mut() {
+        t.translation += dir.0.normalize().extend(0.0) * Vec3::splat(bullet.speed * speed_multiplier) * time.delta_seconds();
         t.translation.z = 10.0;
     }
 }
 
 fn despawn_old_bullets(
     mut commands: Commands,
-    bullet_query: Query<(&SpawnInstant, Entity), With<Bullet>>,
+    time: Res<Time>,
+    bullet_query: Query<(&SpawnInstant, &Bullet, &Transform, Option<&Explosive>, Entity)>,
+    mut ew: EventWriter<ExplosionEvent>,
 ) {
-    for (instant, e) in bullet_query.iter() {
-        if instant.0.elapsed().as_secs_f32() > BULLET_LIFETIME {
+    for (instant, bullet, transform, explosive, e) in bullet_query.iter() {
+        if time.elapsed_seconds() - instant.0 > bullet.lifetime {
+            if let Some(explosive) = explosive {
+                ew.send(ExplosionEvent {
+                    position: transform.translation.truncate(),
+                    radius: explosive.0.radius,
+                    damage: explosive.0.damage,
+                    knockback: bullet.knockback,
+                    source: e,
+                });
+            }
             commands.entity(e).despawn();
         }
     }
 }
 
 fn flip_gun_sprite_y(
-    cursor_position: Res<CursorPosition>,
     mut gun_query: Query<(&mut Sprite, &Transform), With<Gun>>,
 ) {
     if gun_query.is_empty() {
         return;
     }
 
-    let (mut sprite, transform) = gun_query.single_mut();
-    if let Some(cursor_position) = cursor_position.0 {
-        if cursor_position.x > transform.translation.x {
-            sprite.flip_y = false;
-        } else {
-            sprite.flip_y = true;
-        }
+    for (mut sprite, transform) in gun_query.iter_mut() {
+        let direction = transform.rotation.mul_vec3(Vec3::X);
+        sprite.flip_y = direction.x < 0.0;
     }
 }
@@ -8,10 +8,18 @@ pub(crate) mod camera;
 pub(crate) mod animation;
 pub(crate) mod collision;
 pub(crate) mod mainmenu;
+pub(crate) mod gameover;
 pub(crate) mod debug;
+pub(crate) mod settings;
+pub(crate) mod damage_numbers;
+pub(crate) mod experience;
 mod assets;
 pub(crate) mod common;
 pub(crate) mod characters;
+pub(crate) mod weapons;
+pub(crate) mod upgrades;
+pub(crate) mod meta;
+pub(crate) mod rng;
 
 use bevy::prelude::*;
 use bevy::window::close_on_esc;
@@ -41,9 +49,46 @@ use crate::assets::AssetsPlugin;
 use crate::collision::CollisionPlugin;
 use crate::debug::DebugPlugin;
 use crate::mainmenu::MainMenuPlugin;
+use crate::gameover::GameOverPlugin;
+use crate::settings::SettingsPlugin;
+use crate::damage_numbers::DamageNumbersPlugin;
+use crate::experience::ExperiencePlugin;
+use crate::upgrades::passives::PassivesPlugin;
+use crate::upgrades::choice::UpgradeChoicePlugin;
 use crate::characters::monsters::MonstersPlugin;
+use crate::characters::player::CharacterPlugin;
+use crate::characters::ranged::RangedPlugin;
+use crate::characters::bosses::BossesPlugin;
+use crate::characters::elites::ElitesPlugin;
+use crate::characters::director::DirectorPlugin;
+use crate::characters::difficulty::DifficultyPlugin;
+use crate::common::damage::DamagePlugin;
+use crate::common::vitals::VitalsPlugin;
+use crate::common::motion::MotionPlugin;
+use crate::common::status::StatusPlugin;
+use crate::common::stats::StatsPlugin;
+use crate::weapons::projectile::ProjectilePlugin;
+use crate::weapons::aura::AuraPlugin;
+use crate::weapons::orbit::OrbitPlugin;
+use crate::weapons::melee::MeleePlugin;
+use crate::weapons::evolution::EvolutionPlugin;
+use crate::meta::profile::ProfilePlugin;
+use crate::meta::gold::GoldPlugin;
+use crate::meta::power_ups::PowerUpsPlugin;
+use crate::meta::achievements::AchievementsPlugin;
+use crate::rng::RngPlugin;
+
+/// Command line arguments
+#[derive(Parser, Debug)]
+struct Args {
+    /// Seed of the runs, a random seed is picked for each run when omitted
+    #[arg(long)]
+    seed: Option<u64>,
+}
 
 fn main() {
+    let args = Args::parse();
+
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -81,6 +126,37 @@ fn main() {
             // new modules
             AssetsPlugin,
             MonstersPlugin,
+            RangedPlugin,
+            BossesPlugin,
+            ElitesPlugin,
+        ))
+        .add_plugins((
+            DamagePlugin,
+            VitalsPlugin,
+            MotionPlugin,
+            StatusPlugin,
+            StatsPlugin,
+            ProjectilePlugin,
+            AuraPlugin,
+            OrbitPlugin,
+            MeleePlugin,
+            EvolutionPlugin,
+            SettingsPlugin,
+            DamageNumbersPlugin,
+            ExperiencePlugin,
+            PassivesPlugin,
+            UpgradeChoicePlugin,
+        ))
+        .add_plugins((
+            ProfilePlugin,
+            GoldPlugin,
+            PowerUpsPlugin,
+            CharacterPlugin,
+            AchievementsPlugin,
+            DirectorPlugin,
+            DifficultyPlugin,
+            RngPlugin { seed: args.seed },
+            GameOverPlugin,
         ))
         .add_systems(Update, close_on_esc)
 
@@ -1,15 +1,22 @@
 use bevy::prelude::*;
 use belly::prelude::*;
+use belly::widgets::common::Label;
+use crate::meta::profile::ProfileLoadError;
+use crate::settings::Settings;
 use crate::state::GameState;
 
 pub struct MainMenuPlugin;
 
+#[derive(Component, Default)]
+struct DamageNumbersToggle;
+
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(OnEnter(GameState::Loading), load_assets)
             .add_systems(OnEnter(GameState::MainMenu), spawn_menu)
-            .add_systems(OnExit(GameState::MainMenu), despawn_menu);
+            .add_systems(OnExit(GameState::MainMenu), despawn_menu)
+            .add_systems(Update, update_settings_labels.run_if(in_state(GameState::MainMenu)));
     }
 }
 
@@ -20,23 +27,73 @@ fn load_assets(
     commands.add(StyleSheet::load("styles/mainmenu.css"));
 }
 
-fn spawn_menu(mut commands: Commands) {
+fn spawn_menu(mut commands: Commands, load_error: Res<ProfileLoadError>) {
+    let damage_numbers_label = commands.spawn_empty().id();
+    let profile_error = load_error.0.clone().unwrap_or_default();
     commands.add(eml! {
         <div c:menu id="mainmenu">
             <label value="Project Babylone" c:title/>
+            <label value=profile_error c:error/>
             <button
                 on:press=run!(|ctx| {
                     ctx.add(|world: &mut World| {
-                        world.resource_mut::<NextState<GameState>>().set(GameState::GameInit);
+                        world.resource_mut::<NextState<GameState>>().set(GameState::CharacterSelect);
                     });
                 })
             >
                 <label value="Play"/>
             </button>
+            <button
+                on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| {
+                        world.resource_mut::<NextState<GameState>>().set(GameState::PowerUps);
+                    });
+                })
+            >
+                <label value="Power Ups"/>
+            </button>
+            <button
+                on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| {
+                        world.resource_mut::<NextState<GameState>>().set(GameState::Achievements);
+                    });
+                })
+            >
+                <label value="Achievements"/>
+            </button>
+            <button
+                on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| {
+                        let mut settings = world.resource_mut::<Settings>();
+                        settings.show_damage_numbers = !settings.show_damage_numbers;
+                    });
+                })
+            >
+                <label {damage_numbers_label} with=DamageNumbersToggle/>
+            </button>
         </div>
     });
 }
 
+fn update_settings_labels(
+    settings: Res<Settings>,
+    mut label_query: Query<&mut Label, With<DamageNumbersToggle>>,
+) {
+    if label_query.is_empty() {
+        return;
+    }
+
+    let value = format!(
+        "Damage numbers: {}",
+        if settings.show_damage_numbers { "On" } else { "Off" },
+    );
+    for mut label in label_query.iter_mut() {
+        if label.value != value {
+            label.value = value.clone();
+        }
+    }
+}
+
 fn despawn_menu(
     mut elements: Elements,
 ) {
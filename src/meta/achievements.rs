@@ -0,0 +1,385 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy::utils::{HashMap, HashSet};
+use belly::prelude::*;
+use belly::widgets::common::Label;
+use leafwing_manifest::asset_state::SimpleAssetState;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::characters::director::StageManifest;
+use crate::characters::monsters::MonsterManifest;
+use crate::characters::player::CharacterManifest;
+use crate::common::damage::{DamageSet, MonsterKilled};
+use crate::experience::PlayerLevelUp;
+use crate::meta::profile::{save_profile, Profile, Records};
+use crate::state::GameState;
+use crate::weapons::evolution::WeaponEvolved;
+use crate::weapons::manifest::WeaponManifest;
+use crate::world::RunTimer;
+
+/// Width, in characters, of the progress bars of the achievements screen
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+pub(crate) struct AchievementsPlugin;
+
+/// What must be done, over all runs, to unlock an achievement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Condition {
+    /// Kill `count` monsters named `monster`, or any monster when `None`
+    Kills { monster: Option<String>, count: u32 },
+    /// Survive `seconds` in a single run
+    Survive { seconds: f32 },
+    /// Reach `level` in a single run
+    ReachLevel { level: u32 },
+    /// Evolve `count` weapons
+    Evolve { count: u32 },
+}
+
+impl Condition {
+    /// Current and target values of the condition
+    pub fn progress(&self, records: &Records) -> (f32, f32) {
+        match self {
+            Condition::Kills { monster: Some(monster), count } => {
+                (records.kills.get(monster).copied().unwrap_or(0) as f32, *count as f32)
+            }
+            Condition::Kills { monster: None, count } => {
+                (records.kills.values().sum::<u32>() as f32, *count as f32)
+            }
+            Condition::Survive { seconds } => (records.survival_time, *seconds),
+            Condition::ReachLevel { level } => (records.highest_level as f32, *level as f32),
+            Condition::Evolve { count } => (records.evolutions as f32, *count as f32),
+        }
+    }
+
+    pub fn is_met(&self, records: &Records) -> bool {
+        let (current, target) = self.progress(records);
+        current >= target
+    }
+}
+
+/// Content that stays locked until an achievement unlocking it is completed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Unlock {
+    Character(String),
+    Weapon(String),
+    Stage(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct AchievementData {
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+    pub unlocks: Vec<Unlock>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawAchievementData {
+    name: String,
+    description: String,
+    condition: Condition,
+    #[serde(default)]
+    unlocks: Vec<Unlock>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct AchievementManifest(pub HashMap<Id<AchievementData>, AchievementData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawAchievementManifest(Vec<RawAchievementData>);
+
+impl Manifest for AchievementManifest {
+    type RawManifest = RawAchievementManifest;
+    type RawItem = RawAchievementData;
+    type Item = AchievementData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let achievements: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                let item = AchievementData {
+                    name: raw_item.name,
+                    description: raw_item.description,
+                    condition: raw_item.condition,
+                    unlocks: raw_item.unlocks,
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(AchievementManifest(achievements))
+    }
+
+    fn get(&self, id: Id<AchievementData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
+
+impl AchievementManifest {
+    /// Achievements in the order they are listed on the achievements screen
+    fn sorted(&self) -> Vec<&AchievementData> {
+        let mut achievements = self.0.values().collect::<Vec<_>>();
+        achievements.sort_by(|a, b| a.name.cmp(&b.name));
+        achievements
+    }
+}
+
+/// Content unlocked by an achievement the player has not completed yet.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct LockedContent(pub HashSet<Unlock>);
+
+impl LockedContent {
+    pub fn is_locked(&self, unlock: &Unlock) -> bool {
+        self.0.contains(unlock)
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+enum AchievementsAction {
+    Back,
+}
+
+#[derive(Component, Default)]
+struct AchievementListLabel;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<AchievementManifest>("achievements/data.ron")
+            .init_resource::<LockedContent>()
+            .add_event::<AchievementsAction>()
+            .add_systems(OnEnter(SimpleAssetState::Ready), validate_achievements)
+            .add_systems(OnEnter(GameState::Achievements), spawn_achievements_screen)
+            .add_systems(OnExit(GameState::Achievements), despawn_achievements_screen)
+            .add_systems(PostUpdate, record_kills.in_set(DamageSet::Killed))
+            .add_systems(
+                Update,
+                (
+                    record_levels,
+                    record_survival_time.run_if(on_timer(Duration::from_secs(1))),
+                    unlock_achievements,
+                ).chain().run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (
+                    // Evolutions are picked while the game is paused for a level up
+                    record_evolutions,
+                    update_locked_content.run_if(resource_exists::<AchievementManifest>),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_achievements_keyboard,
+                    handle_achievements_actions,
+                    update_achievements_screen,
+                ).chain().run_if(in_state(GameState::Achievements)),
+            );
+    }
+}
+
+/// Drops unlocks of unknown content, and achievements that could never be completed because they
+/// count kills of an unknown monster.
+fn validate_achievements(
+    mut achievement_manifest: ResMut<AchievementManifest>,
+    character_manifest: Res<CharacterManifest>,
+    weapon_manifest: Res<WeaponManifest>,
+    stage_manifest: Res<StageManifest>,
+    monster_manifest: Res<MonsterManifest>,
+) {
+    achievement_manifest.0.retain(|_, achievement| {
+        if let Condition::Kills { monster: Some(monster), .. } = &achievement.condition {
+            if !monster_manifest.0.contains_key(&Id::from_name(monster)) {
+                error!("Achievement {} counts kills of unknown monster {}", achievement.name, monster);
+                return false;
+            }
+        }
+
+        let name = achievement.name.clone();
+        achievement.unlocks.retain(|unlock| {
+            let known = match unlock {
+                Unlock::Character(character) => character_manifest.0.contains_key(&Id::from_name(character)),
+                Unlock::Weapon(weapon) => weapon_manifest.0.contains_key(&Id::from_name(weapon)),
+                Unlock::Stage(stage) => stage_manifest.name == *stage,
+            };
+            if !known {
+                error!("Achievement {} unlocks unknown {:?}", name, unlock);
+            }
+            known
+        });
+        true
+    });
+}
+
+fn record_kills(
+    mut events: EventReader<MonsterKilled>,
+    monster_manifest: Res<MonsterManifest>,
+    mut profile: ResMut<Profile>,
+) {
+    for event in events.read() {
+        if let Some(monster_data) = monster_manifest.0.get(&event.id) {
+            *profile.records.kills.entry(monster_data.name.clone()).or_insert(0) += 1;
+        }
+    }
+}
+
+fn record_levels(
+    mut events: EventReader<PlayerLevelUp>,
+    mut profile: ResMut<Profile>,
+) {
+    for event in events.read() {
+        if event.level > profile.records.highest_level {
+            profile.records.highest_level = event.level;
+        }
+    }
+}
+
+fn record_survival_time(
+    run_timer: Res<RunTimer>,
+    mut profile: ResMut<Profile>,
+) {
+    if run_timer.elapsed_secs() > profile.records.survival_time {
+        profile.records.survival_time = run_timer.elapsed_secs();
+    }
+}
+
+fn record_evolutions(
+    mut events: EventReader<WeaponEvolved>,
+    mut profile: ResMut<Profile>,
+) {
+    for _ in events.read() {
+        profile.records.evolutions += 1;
+    }
+}
+
+fn unlock_achievements(
+    achievement_manifest: Res<AchievementManifest>,
+    mut profile: ResMut<Profile>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+
+    let mut unlocked = false;
+    for achievement in achievement_manifest.0.values() {
+        if profile.achievements.contains(&achievement.name) || !achievement.condition.is_met(&profile.records) {
+            continue;
+        }
+
+        info!("Achievement unlocked: {}", achievement.name);
+        profile.achievements.insert(achievement.name.clone());
+        unlocked = true;
+    }
+
+    if unlocked {
+        save_profile(&profile);
+    }
+}
+
+fn update_locked_content(
+    achievement_manifest: Res<AchievementManifest>,
+    profile: Res<Profile>,
+    mut locked: ResMut<LockedContent>,
+) {
+    if !profile.is_changed() && !achievement_manifest.is_changed() {
+        return;
+    }
+
+    let new_locked = achievement_manifest.0
+        .values()
+        .filter(|achievement| !profile.achievements.contains(&achievement.name))
+        .flat_map(|achievement| achievement.unlocks.iter().cloned())
+        .collect::<HashSet<_>>();
+    if new_locked != locked.0 {
+        locked.0 = new_locked;
+    }
+}
+
+fn spawn_achievements_screen(
+    mut commands: Commands,
+) {
+    let list = commands.spawn_empty().id();
+    commands.add(eml! {
+        <div c:menu id="achievements">
+            <label value="Achievements" c:title/>
+            <label {list} with=AchievementListLabel c:achievements-list/>
+            <button on:press=run!(|ctx| {
+                ctx.add(|world: &mut World| { world.send_event(AchievementsAction::Back); });
+            })>
+                <label value="Back [Backspace]"/>
+            </button>
+        </div>
+    });
+}
+
+fn despawn_achievements_screen(
+    mut elements: Elements,
+) {
+    elements.select("#achievements").remove();
+}
+
+fn handle_achievements_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut ew: EventWriter<AchievementsAction>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        ew.send(AchievementsAction::Back);
+    }
+}
+
+fn handle_achievements_actions(
+    mut events: EventReader<AchievementsAction>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for action in events.read() {
+        match action {
+            AchievementsAction::Back => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+fn progress_bar(current: f32, target: f32) -> String {
+    let ratio = if target > 0.0 { (current / target).clamp(0.0, 1.0) } else { 1.0 };
+    let filled = (ratio * PROGRESS_BAR_WIDTH as f32).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(PROGRESS_BAR_WIDTH - filled))
+}
+
+fn update_achievements_screen(
+    achievement_manifest: Res<AchievementManifest>,
+    profile: Res<Profile>,
+    mut list_query: Query<&mut Label, Added<AchievementListLabel>>,
+) {
+    let Ok(mut label) = list_query.get_single_mut() else {
+        return;
+    };
+
+    label.value = achievement_manifest.sorted()
+        .iter()
+        .map(|achievement| {
+            let (current, target) = achievement.condition.progress(&profile.records);
+            let status = if profile.achievements.contains(&achievement.name) {
+                "Unlocked".to_string()
+            } else {
+                format!("{} {:.0}/{:.0}", progress_bar(current, target), current.min(target), target)
+            };
+            format!("{} - {}\n    {}", achievement.name, achievement.description, status)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use crate::characters::monsters::MonsterManifest;
+use crate::common::damage::{DamageSet, MonsterKilled};
+use crate::meta::profile::{save_profile, Profile};
+use crate::state::GameState;
+
+pub(crate) struct GoldPlugin;
+
+/// Gold earned during the current run, added to the profile once the run is over.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct RunGold(pub u32);
+
+impl Plugin for GoldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<RunGold>()
+            .add_systems(OnEnter(GameState::GameInit), reset_run_gold)
+            .add_systems(OnEnter(GameState::MainMenu), bank_run_gold)
+            .add_systems(PostUpdate, earn_gold.in_set(DamageSet::Killed));
+    }
+}
+
+fn reset_run_gold(mut run_gold: ResMut<RunGold>) {
+    run_gold.0 = 0;
+}
+
+fn earn_gold(
+    mut events: EventReader<MonsterKilled>,
+    monster_manifest: Res<MonsterManifest>,
+    mut run_gold: ResMut<RunGold>,
+) {
+    for event in events.read() {
+        if let Some(monster_data) = monster_manifest.0.get(&event.id) {
+            run_gold.0 += monster_data.gold;
+        }
+    }
+}
+
+/// The run is over: bank its gold, and save everything else it recorded in the profile.
+fn bank_run_gold(mut run_gold: ResMut<RunGold>, mut profile: ResMut<Profile>) {
+    profile.gold += run_gold.0;
+    run_gold.0 = 0;
+    save_profile(&profile);
+}
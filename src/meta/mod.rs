@@ -0,0 +1,4 @@
+pub(crate) mod profile;
+pub(crate) mod gold;
+pub(crate) mod power_ups;
+pub(crate) mod achievements;
@@ -0,0 +1,283 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use belly::prelude::*;
+use belly::widgets::common::Label;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::common::stats::{ModifierSource, StatModifier, Stats};
+use crate::meta::profile::{save_profile, Profile};
+use crate::state::GameState;
+
+pub(crate) struct PowerUpsPlugin;
+
+/// Permanent bonus bought with gold, granted at the start of every run.
+#[derive(Debug, PartialEq)]
+pub(crate) struct PowerUpData {
+    pub name: String,
+    pub description: String,
+    /// Price of the first level, each level costs `cost` more than the previous one
+    pub cost: u32,
+    pub max_level: u32,
+    /// Stat modifiers granted by each level
+    pub modifiers: Vec<StatModifier>,
+}
+
+impl PowerUpData {
+    /// Price of the level following `level`
+    pub fn cost_at(&self, level: u32) -> u32 {
+        self.cost * (level + 1)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawPowerUpData {
+    name: String,
+    description: String,
+    cost: u32,
+    max_level: u32,
+    modifiers: Vec<StatModifier>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct PowerUpManifest(pub HashMap<Id<PowerUpData>, PowerUpData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawPowerUpManifest(Vec<RawPowerUpData>);
+
+impl Manifest for PowerUpManifest {
+    type RawManifest = RawPowerUpManifest;
+    type RawItem = RawPowerUpData;
+    type Item = PowerUpData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let power_ups: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                let item = PowerUpData {
+                    name: raw_item.name,
+                    description: raw_item.description,
+                    cost: raw_item.cost,
+                    max_level: raw_item.max_level,
+                    modifiers: raw_item.modifiers,
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(PowerUpManifest(power_ups))
+    }
+
+    fn get(&self, id: Id<PowerUpData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
+
+impl PowerUpManifest {
+    /// Power ups in the order they are listed in the shop
+    fn sorted(&self) -> Vec<&PowerUpData> {
+        let mut power_ups = self.0.values().collect::<Vec<_>>();
+        power_ups.sort_by(|a, b| a.cost.cmp(&b.cost).then_with(|| a.name.cmp(&b.name)));
+        power_ups
+    }
+}
+
+/// Adds the modifiers of every bought power up level to the player stats.
+pub(crate) fn apply_power_ups(stats: &mut Stats, profile: &Profile, power_up_manifest: &PowerUpManifest) {
+    for (id, power_up) in power_up_manifest.0.iter() {
+        for _ in 0..profile.power_up_level(&power_up.name).min(power_up.max_level) {
+            stats.add_modifiers(ModifierSource::PowerUp(*id), &power_up.modifiers);
+        }
+    }
+}
+
+/// Power up highlighted in the shop.
+#[derive(Resource, Default)]
+struct PowerUpShop {
+    selected: usize,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+enum PowerUpAction {
+    Previous,
+    Next,
+    Buy,
+    Back,
+}
+
+#[derive(Component, Default)]
+struct GoldLabel;
+
+#[derive(Component, Default)]
+struct PowerUpListLabel;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<PowerUpManifest>("power_ups/data.ron")
+            .init_resource::<PowerUpShop>()
+            .add_event::<PowerUpAction>()
+            .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(OnEnter(GameState::PowerUps), (reset_power_up_shop, spawn_power_up_screen))
+            .add_systems(OnExit(GameState::PowerUps), despawn_power_up_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_power_up_keyboard,
+                    handle_power_up_actions,
+                    update_power_up_screen,
+                ).chain().run_if(in_state(GameState::PowerUps)),
+            );
+    }
+}
+
+fn load_assets(
+    mut commands: Commands,
+) {
+    commands.add(StyleSheet::load("styles/powerups.css"));
+}
+
+fn reset_power_up_shop(mut shop: ResMut<PowerUpShop>) {
+    shop.selected = 0;
+}
+
+fn spawn_power_up_screen(
+    mut commands: Commands,
+) {
+    let gold = commands.spawn_empty().id();
+    let list = commands.spawn_empty().id();
+    commands.add(eml! {
+        <div c:menu id="powerups">
+            <label value="Power Ups" c:title/>
+            <label {gold} with=GoldLabel/>
+            <label {list} with=PowerUpListLabel c:powerups-list/>
+            <div c:powerups-actions>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(PowerUpAction::Previous); });
+                })>
+                    <label value="Previous [W]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(PowerUpAction::Next); });
+                })>
+                    <label value="Next [S]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(PowerUpAction::Buy); });
+                })>
+                    <label value="Buy [Enter]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(PowerUpAction::Back); });
+                })>
+                    <label value="Back [Backspace]"/>
+                </button>
+            </div>
+        </div>
+    });
+}
+
+fn despawn_power_up_screen(
+    mut elements: Elements,
+) {
+    elements.select("#powerups").remove();
+}
+
+fn handle_power_up_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut ew: EventWriter<PowerUpAction>,
+) {
+    if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        ew.send(PowerUpAction::Previous);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        ew.send(PowerUpAction::Next);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+        ew.send(PowerUpAction::Buy);
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        ew.send(PowerUpAction::Back);
+    }
+}
+
+fn handle_power_up_actions(
+    mut events: EventReader<PowerUpAction>,
+    mut shop: ResMut<PowerUpShop>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<GameState>>,
+    power_up_manifest: Res<PowerUpManifest>,
+) {
+    let power_ups = power_up_manifest.sorted();
+    let count = power_ups.len();
+
+    for action in events.read() {
+        match *action {
+            PowerUpAction::Previous if count > 0 => shop.selected = (shop.selected + count - 1) % count,
+            PowerUpAction::Next if count > 0 => shop.selected = (shop.selected + 1) % count,
+            PowerUpAction::Buy => {
+                let Some(power_up) = power_ups.get(shop.selected) else {
+                    continue;
+                };
+                let level = profile.power_up_level(&power_up.name);
+                let cost = power_up.cost_at(level);
+                if level >= power_up.max_level || profile.gold < cost {
+                    continue;
+                }
+
+                profile.gold -= cost;
+                profile.power_ups.insert(power_up.name.clone(), level + 1);
+                save_profile(&profile);
+            }
+            PowerUpAction::Back => next_state.set(GameState::MainMenu),
+            _ => {}
+        }
+    }
+}
+
+fn update_power_up_screen(
+    shop: Res<PowerUpShop>,
+    profile: Res<Profile>,
+    power_up_manifest: Res<PowerUpManifest>,
+    mut gold_query: Query<&mut Label, (With<GoldLabel>, Without<PowerUpListLabel>)>,
+    mut list_query: Query<&mut Label, (With<PowerUpListLabel>, Without<GoldLabel>)>,
+) {
+    if !shop.is_changed() && !profile.is_changed() {
+        return;
+    }
+
+    if let Ok(mut label) = gold_query.get_single_mut() {
+        label.value = format!("Gold: {}", profile.gold);
+    }
+
+    let Ok(mut label) = list_query.get_single_mut() else {
+        return;
+    };
+    label.value = power_up_manifest.sorted()
+        .iter()
+        .enumerate()
+        .map(|(i, power_up)| {
+            let level = profile.power_up_level(&power_up.name);
+            let marker = if i == shop.selected { ">" } else { " " };
+            let price = if level >= power_up.max_level {
+                "Max".to_string()
+            } else {
+                format!("{} gold", power_up.cost_at(level))
+            };
+            format!("{} {} {}/{} - {} - {}", marker, power_up.name, level, power_up.max_level, power_up.description, price)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
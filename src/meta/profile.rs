@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+/// Version written in new saves, bump it and add a migration step whenever `Profile` changes.
+pub const PROFILE_VERSION: u32 = 2;
+const DATA_DIR_NAME: &str = "project-babylone";
+const PROFILE_FILE_NAME: &str = "profile.ron";
+/// Extension of the copy kept when a save can't be loaded
+const BROKEN_PROFILE_EXTENSION: &str = "ron.broken";
+
+pub(crate) struct ProfilePlugin;
+
+/// Progression kept from one run to the next, stored in the platform data directory.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Profile {
+    pub version: u32,
+    pub gold: u32,
+    /// Level of the bought power ups, by name
+    #[serde(default)]
+    pub power_ups: BTreeMap<String, u32>,
+    /// Names of the unlocked achievements
+    #[serde(default)]
+    pub achievements: BTreeSet<String>,
+    #[serde(default)]
+    pub records: Records,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            version: PROFILE_VERSION,
+            gold: 0,
+            power_ups: BTreeMap::new(),
+            achievements: BTreeSet::new(),
+            records: Records::default(),
+        }
+    }
+}
+
+/// Totals and bests over every run, achievements conditions are checked against them.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct Records {
+    /// Monsters killed, by monster name
+    pub kills: BTreeMap<String, u32>,
+    /// Longest run, in seconds
+    pub survival_time: f32,
+    pub highest_level: u32,
+    pub evolutions: u32,
+}
+
+/// Save format 1, before achievements.
+#[derive(Deserialize)]
+struct ProfileV1 {
+    gold: u32,
+    #[serde(default)]
+    power_ups: BTreeMap<String, u32>,
+}
+
+impl From<ProfileV1> for Profile {
+    fn from(profile: ProfileV1) -> Self {
+        Self {
+            gold: profile.gold,
+            power_ups: profile.power_ups,
+            ..default()
+        }
+    }
+}
+
+impl Profile {
+    pub fn power_up_level(&self, name: &str) -> u32 {
+        self.power_ups.get(name).copied().unwrap_or(0)
+    }
+}
+
+/// Why the profile could not be loaded, shown on the main menu.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ProfileLoadError(pub Option<String>);
+
+/// Only the version is read first, to know how to parse the rest of the save.
+#[derive(Deserialize)]
+struct ProfileHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub(crate) enum ProfileError {
+    /// None of the environment variables locating the data directory is set
+    NoDataDir,
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    /// The save was written by a newer version of the game
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::NoDataDir => write!(f, "could not locate the data directory"),
+            ProfileError::Io(err) => write!(f, "could not access the save file: {}", err),
+            ProfileError::Parse(err) => write!(f, "the save file is corrupted: {}", err),
+            ProfileError::Serialize(err) => write!(f, "could not serialize the profile: {}", err),
+            ProfileError::UnsupportedVersion(version) => write!(
+                f,
+                "the save file version {} is not supported (expected {} or lower)",
+                version, PROFILE_VERSION,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<io::Error> for ProfileError {
+    fn from(err: io::Error) -> Self {
+        ProfileError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ProfileError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ProfileError::Parse(err)
+    }
+}
+
+impl From<ron::Error> for ProfileError {
+    fn from(err: ron::Error) -> Self {
+        ProfileError::Serialize(err)
+    }
+}
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ProfileLoadError>()
+            .add_systems(PreStartup, load_profile);
+    }
+}
+
+/// `$XDG_DATA_HOME` (or `~/.local/share`) on Linux, `~/Library/Application Support` on macOS
+/// and `%APPDATA%` on Windows.
+fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+    };
+
+    base.map(|dir| dir.join(DATA_DIR_NAME))
+}
+
+fn profile_path() -> Result<PathBuf, ProfileError> {
+    data_dir()
+        .map(|dir| dir.join(PROFILE_FILE_NAME))
+        .ok_or(ProfileError::NoDataDir)
+}
+
+/// Reads the profile, a missing save meaning a new profile.
+fn read_profile() -> Result<Profile, ProfileError> {
+    let path = profile_path()?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Profile::default()),
+        Err(err) => return Err(err.into()),
+    };
+
+    parse_profile(&contents)
+}
+
+fn parse_profile(contents: &str) -> Result<Profile, ProfileError> {
+    let header: ProfileHeader = ron::from_str(contents)?;
+    migrate(header.version, contents)
+}
+
+/// Parses a save written with `version`, upgrading it to `PROFILE_VERSION`.
+/// Older versions are handled here one step at a time.
+fn migrate(version: u32, contents: &str) -> Result<Profile, ProfileError> {
+    match version {
+        1 => Ok(ron::from_str::<ProfileV1>(contents)?.into()),
+        PROFILE_VERSION => Ok(ron::from_str(contents)?),
+        _ => Err(ProfileError::UnsupportedVersion(version)),
+    }
+}
+
+/// Writes the profile to a temporary file first, so that a crash can't leave a half written save.
+fn write_profile(profile: &Profile) -> Result<(), ProfileError> {
+    let path = profile_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let contents = ron::ser::to_string_pretty(profile, PrettyConfig::default())?;
+    let tmp_path = path.with_extension("ron.tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, &path)?;
+
+    Ok(())
+}
+
+/// Saves the profile, failures are logged but never interrupt the game.
+pub(crate) fn save_profile(profile: &Profile) {
+    if let Err(err) = write_profile(profile) {
+        error!("Could not save the profile: {}", err);
+    }
+}
+
+fn load_profile(mut commands: Commands, mut load_error: ResMut<ProfileLoadError>) {
+    let profile = match read_profile() {
+        Ok(profile) => profile,
+        Err(err) => {
+            error!("Could not load the profile: {}", err);
+            // Keep the broken save around instead of overwriting it on the next save
+            if let Ok(path) = profile_path() {
+                if path.exists() {
+                    let _ = fs::rename(&path, path.with_extension(BROKEN_PROFILE_EXTENSION));
+                }
+            }
+            load_error.0 = Some(format!("Could not load the profile, {}. A new profile was started.", err));
+            Profile::default()
+        }
+    };
+
+    commands.insert_resource(profile);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_v1_save() {
+        let profile = parse_profile(r#"(version: 1, gold: 120, power_ups: {"Might": 2})"#).unwrap();
+
+        assert_eq!(profile.version, PROFILE_VERSION);
+        assert_eq!(profile.gold, 120);
+        assert_eq!(profile.power_up_level("Might"), 2);
+        assert!(profile.achievements.is_empty());
+    }
+
+    #[test]
+    fn loads_current_version_as_is() {
+        let profile = Profile {
+            gold: 42,
+            achievements: BTreeSet::from(["Survivor".to_string()]),
+            ..default()
+        };
+        let contents = ron::to_string(&profile).unwrap();
+
+        let loaded = parse_profile(&contents).unwrap();
+        assert_eq!(loaded.version, PROFILE_VERSION);
+        assert_eq!(loaded.gold, 42);
+        assert!(loaded.achievements.contains("Survivor"));
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        let version = PROFILE_VERSION + 1;
+        let result = parse_profile(&format!("(version: {}, gold: 0)", version));
+
+        assert!(matches!(result, Err(ProfileError::UnsupportedVersion(v)) if v == version));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(parse_profile("not a profile"), Err(ProfileError::Parse(_))));
+        assert!(matches!(migrate(PROFILE_VERSION, "(gold: )"), Err(ProfileError::Parse(_))));
+    }
+}
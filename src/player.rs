@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::consts::*;
+use crate::common::motion::Stun;
+use crate::common::stats::{Stat, Stats};
+use crate::common::status::StatusEffects;
+use crate::common::vitals::Health;
 use crate::resources::CursorPosition;
 use crate::state::GameState;
 
@@ -9,28 +12,24 @@ pub struct PlayerPlugin;
 #[derive(Component)]
 pub struct Player;
 
-#[derive(Component)]
-pub struct Health(pub f32);
+/// Normalized direction the player is moving in, zero when standing still.
+#[derive(Component, Default, Clone, Copy)]
+pub struct MoveDirection(pub Vec2);
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerState {
     #[default]
     Idle,
     Run,
 }
 
-#[derive(Event)]
-pub struct PlayerEnemyCollisionEvent;
-
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_event::<PlayerEnemyCollisionEvent>()
             .add_systems(
                 Update,
                 (
                     handle_player_input,
-                    handle_player_enemy_collision_events,
                     handle_player_death,
                     flip_player_sprite_x,
                 )
@@ -41,14 +40,14 @@ impl Plugin for PlayerPlugin {
 
 fn handle_player_input(
     time: Res<Time>,
-    mut player_query: Query<(&mut Transform, &mut PlayerState), With<Player>>,
+    mut player_query: Query<(&mut Transform, &mut PlayerState, &mut MoveDirection, &Stats, &StatusEffects, &Stun), With<Player>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
-    let (mut transform, mut player_state) = player_query.single_mut();
+    let (mut transform, mut player_state, mut move_direction, stats, effects, stun) = player_query.single_mut();
     let w_key = keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp);
     let a_key = keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft);
     let s_key = keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown);
@@ -69,26 +68,17 @@ fn handle_player_input(
         delta.x += 1.0;
     }
     delta = delta.normalize();
+    if stun.is_active() {
+        delta = Vec2::ZERO;
+    }
 
     if delta.is_finite() && delta != Vec2::ZERO {
-        transform.translation += Vec3::new(delta.x, delta.y, 0.0) * PLAYER_SPEED * time.delta_seconds();
-        *player_state = PlayerState::Run;
+        transform.translation += Vec3::new(delta.x, delta.y, 0.0) * stats.get(Stat::MoveSpeed) * effects.speed_multiplier() * time.delta_seconds();
+        player_state.set_if_neq(PlayerState::Run);
+        move_direction.0 = delta;
     } else {
-        *player_state = PlayerState::Idle;
-    }
-}
-
-fn handle_player_enemy_collision_events(
-    mut player_query: Query<&mut Health, With<Player>>,
-    mut events: EventReader<PlayerEnemyCollisionEvent>,
-) {
-    if player_query.is_empty() {
-        return;
-    }
-
-    let mut health = player_query.single_mut();
-    for _ in events.read() {
-        health.0 -= ENEMY_DAMAGE;
+        player_state.set_if_neq(PlayerState::Idle);
+        move_direction.0 = Vec2::ZERO;
     }
 }
 
@@ -101,8 +91,8 @@ fn handle_player_death(
     }
 
     let health = player_query.single();
-    if health.0 <= 0.0 {
-        next_state.set(GameState::MainMenu);
+    if health.is_dead() {
+        next_state.set(GameState::GameOver);
     }
 }
 
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::state::GameState;
+
+/// Seeds the run random number generators at `GameInit`, from `seed` or a random seed.
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+/// Seed requested on the command line, every run reuses it when set.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct SeedOverride(Option<u64>);
+
+/// Random number generators of the current run, all derived from `seed`.
+///
+/// Each stream is independent, so that drawing more numbers from one of them (e.g. a new weapon
+/// with spread) does not change what the others produce for the same seed.
+#[derive(Resource)]
+pub(crate) struct RunRng {
+    pub seed: u64,
+    /// Monster placement, picks and elite affixes
+    pub spawning: StdRng,
+    /// Upgrades offered on level up
+    pub loot: StdRng,
+    /// Projectile spread
+    pub spread: StdRng,
+    /// Critical hits and status effect procs
+    pub combat: StdRng,
+    /// World decorations
+    pub world: StdRng,
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        let mut root = StdRng::seed_from_u64(seed);
+        // Streams are drawn in a fixed order, new streams must be added at the end
+        let mut stream = || StdRng::from_rng(&mut root).expect("StdRng never fails to seed");
+
+        Self {
+            seed,
+            spawning: stream(),
+            loot: stream(),
+            spread: stream(),
+            combat: stream(),
+            world: stream(),
+        }
+    }
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(SeedOverride(self.seed))
+            .insert_resource(RunRng::new(self.seed.unwrap_or_else(random)))
+            .add_systems(OnEnter(GameState::GameInit), seed_run_rng);
+    }
+}
+
+pub(crate) fn seed_run_rng(mut run_rng: ResMut<RunRng>, seed_override: Res<SeedOverride>) {
+    let seed = seed_override.0.unwrap_or_else(random);
+    info!("Run seed: {}", seed);
+    *run_rng = RunRng::new(seed);
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+pub struct SettingsPlugin;
+
+/// Player preferences, edited from the main menu.
+#[derive(Resource, Debug, Clone)]
+pub struct Settings {
+    pub show_damage_numbers: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            show_damage_numbers: true,
+        }
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+    }
+}
+
+pub fn damage_numbers_enabled(settings: Res<Settings>) -> bool {
+    settings.show_damage_numbers
+}
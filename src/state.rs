@@ -5,6 +5,16 @@ pub enum GameState {
     #[default]
     Loading,
     MainMenu,
+    /// Shop selling permanent bonuses, opened from the main menu
+    PowerUps,
+    /// Picking the character of the next run, between the main menu and `GameInit`
+    CharacterSelect,
+    /// List of the achievements and their progress, opened from the main menu
+    Achievements,
     GameInit,
     InGame,
+    /// Gameplay is paused while the player handles a level up
+    LevelUp,
+    /// The player died, the run summary is shown until going back to the main menu
+    GameOver,
 }
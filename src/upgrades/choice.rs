@@ -0,0 +1,538 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use belly::prelude::*;
+use belly::widgets::common::Label;
+use leafwing_manifest::identifier::Id;
+use rand::prelude::*;
+
+use crate::common::stats::{ModifierSource, Stats};
+use crate::consts::*;
+use crate::experience::PendingLevelUps;
+use crate::meta::achievements::{LockedContent, Unlock};
+use crate::player::Player;
+use crate::resources::GlobalTextureAtlas;
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::upgrades::passives::{PassiveData, PassiveManifest, Passives};
+use crate::upgrades::rarity::Rarity;
+use crate::weapons::evolution::{EvolutionData, EvolutionManifest, WeaponEvolved};
+use crate::weapons::inventory::{equip_weapon, evolve_weapon, Weapon, WeaponInventory};
+use crate::weapons::manifest::{WeaponData, WeaponManifest};
+
+pub const UPGRADE_CHOICES: usize = 3;
+pub const REROLLS_PER_RUN: u32 = 2;
+pub const BANISHES_PER_RUN: u32 = 2;
+
+pub(crate) struct UpgradeChoicePlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Upgrade {
+    Weapon(Id<WeaponData>),
+    Passive(Id<PassiveData>),
+    /// Replaces a max level weapon with its evolved form
+    Evolution(Id<EvolutionData>),
+}
+
+/// Upgrades currently offered on the level up screen.
+#[derive(Resource, Default)]
+struct UpgradeOffer {
+    choices: Vec<Upgrade>,
+    /// Choice highlighted for keyboard selection
+    selected: usize,
+    /// Whether the next picked choice is banished instead of applied
+    banishing: bool,
+    /// Set when `choices` must be (re)filled on the next frame
+    stale: bool,
+    rerolls: u32,
+    banishes: u32,
+    /// Upgrades never offered again during the run
+    banished: HashSet<Upgrade>,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+enum UpgradeAction {
+    Pick(usize),
+    Reroll,
+    Skip,
+    ToggleBanish,
+}
+
+#[derive(Component)]
+struct UpgradeChoiceLabel(usize);
+
+#[derive(Component, Default)]
+struct RerollLabel;
+
+#[derive(Component, Default)]
+struct BanishLabel;
+
+impl Plugin for UpgradeChoicePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<UpgradeOffer>()
+            .add_event::<UpgradeAction>()
+            .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(OnEnter(GameState::GameInit), reset_upgrade_offer)
+            .add_systems(OnEnter(GameState::LevelUp), (open_upgrade_offer, spawn_upgrade_screen))
+            .add_systems(OnExit(GameState::LevelUp), despawn_upgrade_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_upgrade_keyboard,
+                    roll_upgrade_offer,
+                    handle_upgrade_actions,
+                    update_upgrade_screen,
+                ).chain().run_if(in_state(GameState::LevelUp)),
+            );
+    }
+}
+
+fn load_assets(
+    mut commands: Commands,
+) {
+    commands.add(StyleSheet::load("styles/levelup.css"));
+}
+
+fn reset_upgrade_offer(mut offer: ResMut<UpgradeOffer>) {
+    *offer = UpgradeOffer {
+        rerolls: REROLLS_PER_RUN,
+        banishes: BANISHES_PER_RUN,
+        ..default()
+    };
+}
+
+fn open_upgrade_offer(mut offer: ResMut<UpgradeOffer>) {
+    offer.choices.clear();
+    offer.selected = 0;
+    offer.banishing = false;
+    offer.stale = true;
+}
+
+fn spawn_upgrade_screen(
+    mut commands: Commands,
+) {
+    let choice_0 = commands.spawn(UpgradeChoiceLabel(0)).id();
+    let choice_1 = commands.spawn(UpgradeChoiceLabel(1)).id();
+    let choice_2 = commands.spawn(UpgradeChoiceLabel(2)).id();
+    let reroll = commands.spawn_empty().id();
+    let banish = commands.spawn_empty().id();
+    commands.add(eml! {
+        <div id="levelup" c:levelup>
+            <label value="Level up!" c:levelup-title/>
+            <div c:levelup-choices>
+                <button id="upgrade-0" c:levelup-choice on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::Pick(0)); });
+                })>
+                    <label {choice_0}/>
+                </button>
+                <button id="upgrade-1" c:levelup-choice on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::Pick(1)); });
+                })>
+                    <label {choice_1}/>
+                </button>
+                <button id="upgrade-2" c:levelup-choice on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::Pick(2)); });
+                })>
+                    <label {choice_2}/>
+                </button>
+            </div>
+            <div c:levelup-actions>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::Reroll); });
+                })>
+                    <label {reroll} with=RerollLabel/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::Skip); });
+                })>
+                    <label value="Skip [X]"/>
+                </button>
+                <button on:press=run!(|ctx| {
+                    ctx.add(|world: &mut World| { world.send_event(UpgradeAction::ToggleBanish); });
+                })>
+                    <label {banish} with=BanishLabel/>
+                </button>
+            </div>
+        </div>
+    });
+}
+
+fn despawn_upgrade_screen(
+    mut elements: Elements,
+) {
+    elements.select("#levelup").remove();
+}
+
+fn handle_upgrade_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut offer: ResMut<UpgradeOffer>,
+    mut ew: EventWriter<UpgradeAction>,
+) {
+    let count = offer.choices.len();
+
+    for (i, key) in [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3].into_iter().enumerate() {
+        if keyboard_input.just_pressed(key) {
+            ew.send(UpgradeAction::Pick(i));
+        }
+    }
+
+    if count > 0 {
+        if keyboard_input.any_just_pressed([KeyCode::ArrowLeft, KeyCode::ArrowUp, KeyCode::KeyA, KeyCode::KeyW]) {
+            offer.selected = (offer.selected + count - 1) % count;
+        }
+        if keyboard_input.any_just_pressed([KeyCode::ArrowRight, KeyCode::ArrowDown, KeyCode::KeyD, KeyCode::KeyS]) {
+            offer.selected = (offer.selected + 1) % count;
+        }
+        if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+            ew.send(UpgradeAction::Pick(offer.selected));
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        ew.send(UpgradeAction::Reroll);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyX) {
+        ew.send(UpgradeAction::Skip);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        ew.send(UpgradeAction::ToggleBanish);
+    }
+}
+
+/// Evolutions whose ingredients are all owned by the player.
+fn ready_evolutions(
+    evolution_manifest: &EvolutionManifest,
+    weapon_manifest: &WeaponManifest,
+    inventory: &WeaponInventory,
+    passives: &Passives,
+    weapon_query: &Query<&Weapon>,
+) -> Vec<Upgrade> {
+    evolution_manifest.0
+        .iter()
+        .filter(|(_, evolution)| weapon_query.iter_many(&inventory.0).any(|weapon| {
+            weapon_manifest.0
+                .get(&weapon.id)
+                .is_some_and(|weapon_data| evolution.is_ready(weapon, weapon_data, passives))
+        }))
+        .map(|(id, _)| Upgrade::Evolution(*id))
+        .collect()
+}
+
+/// Upgrades that can currently be offered, with their weight: new items while a slot is free,
+/// and owned items below their max level. Evolved weapons only come from their evolution, and
+/// weapons locked behind an achievement are never offered.
+fn upgrade_candidates(
+    weapon_manifest: &WeaponManifest,
+    passive_manifest: &PassiveManifest,
+    evolution_manifest: &EvolutionManifest,
+    locked: &LockedContent,
+    inventory: &WeaponInventory,
+    passives: &Passives,
+    weapon_query: &Query<&Weapon>,
+) -> Vec<(Upgrade, Rarity)> {
+    let owned_weapons = weapon_query
+        .iter_many(&inventory.0)
+        .map(|weapon| (weapon.id, weapon.level))
+        .collect::<Vec<_>>();
+
+    let weapons = weapon_manifest.0
+        .iter()
+        .filter(|(id, weapon)| match owned_weapons.iter().find(|(owned, _)| owned == *id) {
+            Some((_, level)) => *level < weapon.max_level,
+            None => !inventory.is_full()
+                && !evolution_manifest.is_evolved_form(**id)
+                && !locked.is_locked(&Unlock::Weapon(weapon.name.clone())),
+        })
+        .map(|(id, weapon)| (Upgrade::Weapon(*id), weapon.rarity));
+
+    let passives = passive_manifest.0
+        .iter()
+        .filter(|(id, passive)| match passives.0.get(*id) {
+            Some(level) => *level < passive.max_level,
+            None => !passives.is_full(),
+        })
+        .map(|(id, passive)| (Upgrade::Passive(*id), passive.rarity));
+
+    weapons.chain(passives).collect()
+}
+
+fn roll_upgrade_offer(
+    mut offer: ResMut<UpgradeOffer>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+    evolution_manifest: Res<EvolutionManifest>,
+    locked: Res<LockedContent>,
+    player_query: Query<(&WeaponInventory, &Passives), With<Player>>,
+    weapon_query: Query<&Weapon>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if !offer.stale || player_query.is_empty() {
+        return;
+    }
+    offer.stale = false;
+
+    let (inventory, passives) = player_query.single();
+
+    // Ready evolutions are always offered, before any rolled upgrade
+    for evolution in ready_evolutions(&evolution_manifest, &weapon_manifest, inventory, passives, &weapon_query) {
+        if offer.choices.len() < UPGRADE_CHOICES && !offer.banished.contains(&evolution) && !offer.choices.contains(&evolution) {
+            offer.choices.push(evolution);
+        }
+    }
+
+    let candidates = upgrade_candidates(&weapon_manifest, &passive_manifest, &evolution_manifest, &locked, inventory, passives, &weapon_query)
+        .into_iter()
+        .filter(|(upgrade, _)| !offer.banished.contains(upgrade) && !offer.choices.contains(upgrade))
+        .collect::<Vec<_>>();
+
+    let missing = UPGRADE_CHOICES.saturating_sub(offer.choices.len()).min(candidates.len());
+    let new_choices = candidates
+        .choose_multiple_weighted(&mut run_rng.loot, missing, |(_, rarity)| rarity.weight())
+        .map(|chosen| chosen.map(|(upgrade, _)| *upgrade).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    offer.choices.extend(new_choices);
+    offer.selected = offer.selected.min(offer.choices.len().saturating_sub(1));
+}
+
+fn handle_upgrade_actions(
+    mut commands: Commands,
+    mut events: EventReader<UpgradeAction>,
+    mut offer: ResMut<UpgradeOffer>,
+    mut pending: ResMut<PendingLevelUps>,
+    mut next_state: ResMut<NextState<GameState>>,
+    handle: Res<GlobalTextureAtlas>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+    evolution_manifest: Res<EvolutionManifest>,
+    mut player_query: Query<(&mut WeaponInventory, &mut Passives, &mut Stats), With<Player>>,
+    mut weapon_query: Query<&mut Weapon>,
+    mut evolved_ew: EventWriter<WeaponEvolved>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (mut inventory, mut passives, mut stats) = player_query.single_mut();
+
+    for action in events.read() {
+        // Actions of the same frame past a resolved level up are dropped
+        if offer.stale {
+            break;
+        }
+
+        match *action {
+            UpgradeAction::Reroll => {
+                if offer.rerolls > 0 {
+                    offer.rerolls -= 1;
+                    offer.choices.clear();
+                    offer.stale = true;
+                }
+                continue;
+            }
+            UpgradeAction::ToggleBanish => {
+                offer.banishing = offer.banishes > 0 && !offer.banishing;
+                continue;
+            }
+            UpgradeAction::Pick(i) if offer.banishing => {
+                if i < offer.choices.len() {
+                    let upgrade = offer.choices.remove(i);
+                    offer.banished.insert(upgrade);
+                    offer.banishes -= 1;
+                    offer.banishing = false;
+                    offer.stale = true;
+                }
+                continue;
+            }
+            UpgradeAction::Pick(i) => {
+                let Some(upgrade) = offer.choices.get(i).copied() else {
+                    continue;
+                };
+
+                match upgrade {
+                    Upgrade::Weapon(id) => {
+                        apply_weapon_upgrade(&mut commands, &mut inventory, &mut weapon_query, id, &weapon_manifest, &handle);
+                    }
+                    Upgrade::Passive(id) => {
+                        let Some(passive) = passive_manifest.0.get(&id) else {
+                            continue;
+                        };
+                        *passives.0.entry(id).or_insert(0) += 1;
+                        // Each level stacks the passive modifiers once more
+                        stats.add_modifiers(ModifierSource::Passive(id), &passive.modifiers);
+                    }
+                    Upgrade::Evolution(id) => {
+                        let Some(evolution) = evolution_manifest.0.get(&id) else {
+                            continue;
+                        };
+                        if apply_evolution(&mut commands, &inventory, &mut weapon_query, evolution, &weapon_manifest, &handle) {
+                            evolved_ew.send(WeaponEvolved {
+                                weapon: evolution.weapon,
+                                evolved: evolution.evolved,
+                            });
+                        }
+                    }
+                }
+            }
+            UpgradeAction::Skip => {}
+        }
+
+        // The level up is resolved, move on to the next pending one or resume the run
+        pending.0 = pending.0.saturating_sub(1);
+        if pending.0 == 0 {
+            next_state.set(GameState::InGame);
+        }
+        offer.choices.clear();
+        offer.selected = 0;
+        offer.banishing = false;
+        offer.stale = true;
+    }
+}
+
+/// Levels up the weapon if already equipped, equips it otherwise.
+fn apply_weapon_upgrade(
+    commands: &mut Commands,
+    inventory: &mut WeaponInventory,
+    weapon_query: &mut Query<&mut Weapon>,
+    id: Id<WeaponData>,
+    weapon_manifest: &WeaponManifest,
+    handle: &GlobalTextureAtlas,
+) {
+    let Some(weapon_data) = weapon_manifest.0.get(&id) else {
+        return;
+    };
+
+    let mut owned = weapon_query.iter_many_mut(&inventory.0);
+    while let Some(mut weapon) = owned.fetch_next() {
+        if weapon.id == id {
+            weapon.level = (weapon.level + 1).min(weapon_data.max_level);
+            return;
+        }
+    }
+
+    equip_weapon(commands, inventory, id, weapon_data, handle);
+}
+
+/// Replaces the evolution base weapon with the evolved one, in the same slot.
+/// Returns whether the base weapon was found.
+fn apply_evolution(
+    commands: &mut Commands,
+    inventory: &WeaponInventory,
+    weapon_query: &mut Query<&mut Weapon>,
+    evolution: &EvolutionData,
+    weapon_manifest: &WeaponManifest,
+    handle: &GlobalTextureAtlas,
+) -> bool {
+    let Some(evolved) = weapon_manifest.0.get(&evolution.evolved) else {
+        return false;
+    };
+
+    for &weapon_entity in inventory.0.iter() {
+        let Ok(mut weapon) = weapon_query.get_mut(weapon_entity) else {
+            continue;
+        };
+        if weapon.id == evolution.weapon {
+            evolve_weapon(commands, weapon_entity, &mut weapon, evolution.evolved, evolved, handle);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn upgrade_text(
+    upgrade: Upgrade,
+    weapon_manifest: &WeaponManifest,
+    passive_manifest: &PassiveManifest,
+    evolution_manifest: &EvolutionManifest,
+    passives: &Passives,
+    owned_weapons: &[Weapon],
+) -> (String, Rarity) {
+    match upgrade {
+        Upgrade::Weapon(id) => {
+            let Some(weapon) = weapon_manifest.0.get(&id) else {
+                return (String::new(), Rarity::Common);
+            };
+            let text = match owned_weapons.iter().find(|owned| owned.id == id) {
+                Some(owned) => format!("{}\nLevel {}\nDamage +{:.0}%", weapon.name, owned.level + 1, WEAPON_LEVEL_DAMAGE_BONUS * 100.0),
+                None => format!("{}\nNew weapon", weapon.name),
+            };
+            (text, weapon.rarity)
+        }
+        Upgrade::Passive(id) => {
+            let Some(passive) = passive_manifest.0.get(&id) else {
+                return (String::new(), Rarity::Common);
+            };
+            let level = passives.level(id);
+            let text = if level == 0 {
+                format!("{}\nNew passive\n{}", passive.name, passive.description)
+            } else {
+                format!("{}\nLevel {}\n{}", passive.name, level + 1, passive.description)
+            };
+            (text, passive.rarity)
+        }
+        Upgrade::Evolution(id) => {
+            let Some((weapon, evolved)) = evolution_manifest.0
+                .get(&id)
+                .and_then(|evolution| weapon_manifest.0.get(&evolution.weapon).zip(weapon_manifest.0.get(&evolution.evolved)))
+            else {
+                return (String::new(), Rarity::Legendary);
+            };
+            (format!("{}\nEvolution\nReplaces {}", evolved.name, weapon.name), Rarity::Legendary)
+        }
+    }
+}
+
+fn update_upgrade_screen(
+    offer: Res<UpgradeOffer>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+    evolution_manifest: Res<EvolutionManifest>,
+    player_query: Query<(&WeaponInventory, &Passives), With<Player>>,
+    weapon_query: Query<&Weapon>,
+    mut choice_query: Query<(&mut Label, &UpgradeChoiceLabel), (Without<RerollLabel>, Without<BanishLabel>)>,
+    mut reroll_query: Query<&mut Label, (With<RerollLabel>, Without<BanishLabel>)>,
+    mut banish_query: Query<&mut Label, (With<BanishLabel>, Without<RerollLabel>)>,
+    mut elements: Elements,
+) {
+    if player_query.is_empty() || !offer.is_changed() {
+        return;
+    }
+
+    let (inventory, passives) = player_query.single();
+    let owned_weapons = weapon_query.iter_many(&inventory.0).copied().collect::<Vec<_>>();
+
+    for (mut label, choice) in choice_query.iter_mut() {
+        let selector = format!("#upgrade-{}", choice.0);
+        for rarity in [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Legendary] {
+            elements.select(&selector).remove_class(rarity.class());
+        }
+        elements.select(&selector).remove_class("selected");
+
+        let Some(upgrade) = offer.choices.get(choice.0) else {
+            elements.select(&selector).add_class("hidden");
+            continue;
+        };
+
+        let (text, rarity) = upgrade_text(*upgrade, &weapon_manifest, &passive_manifest, &evolution_manifest, passives, &owned_weapons);
+        elements.select(&selector).remove_class("hidden");
+        elements.select(&selector).add_class(rarity.class());
+        if choice.0 == offer.selected {
+            elements.select(&selector).add_class("selected");
+        }
+        label.value = text;
+    }
+
+    if let Ok(mut label) = reroll_query.get_single_mut() {
+        label.value = format!("Reroll ({}) [R]", offer.rerolls);
+    }
+    if let Ok(mut label) = banish_query.get_single_mut() {
+        label.value = format!("Banish ({}) [B]", offer.banishes);
+    }
+
+    if offer.banishing {
+        elements.select("#levelup").add_class("banishing");
+    } else {
+        elements.select("#levelup").remove_class("banishing");
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod rarity;
+pub(crate) mod passives;
+pub(crate) mod choice;
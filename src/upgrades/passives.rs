@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::common::stats::StatModifier;
+use crate::consts::*;
+use crate::upgrades::rarity::Rarity;
+
+pub(crate) struct PassivesPlugin;
+
+impl Plugin for PassivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_manifest::<PassiveManifest>("passives/data.ron");
+    }
+}
+
+/// Passive items held by the player, with their level.
+#[derive(Component, Debug, Default)]
+pub(crate) struct Passives(pub HashMap<Id<PassiveData>, u32>);
+
+impl Passives {
+    pub fn is_full(&self) -> bool {
+        self.0.len() >= MAX_PASSIVE_SLOTS
+    }
+
+    pub fn level(&self, id: Id<PassiveData>) -> u32 {
+        self.0.get(&id).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct PassiveData {
+    pub name: String,
+    pub description: String,
+    pub rarity: Rarity,
+    pub max_level: u32,
+    /// Stat modifiers granted by each level
+    pub modifiers: Vec<StatModifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawPassiveData {
+    name: String,
+    description: String,
+    #[serde(default)]
+    rarity: Rarity,
+    max_level: u32,
+    modifiers: Vec<StatModifier>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct PassiveManifest(pub HashMap<Id<PassiveData>, PassiveData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawPassiveManifest(Vec<RawPassiveData>);
+
+impl Manifest for PassiveManifest {
+    type RawManifest = RawPassiveManifest;
+    type RawItem = RawPassiveData;
+    type Item = PassiveData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let passives: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                let item = PassiveData {
+                    name: raw_item.name,
+                    description: raw_item.description,
+                    rarity: raw_item.rarity,
+                    max_level: raw_item.max_level,
+                    modifiers: raw_item.modifiers,
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(PassiveManifest(passives))
+    }
+
+    fn get(&self, id: Id<PassiveData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// How often an upgrade shows up on the level up screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    /// Relative chance of being offered
+    pub fn weight(&self) -> f32 {
+        match self {
+            Rarity::Common => 60.0,
+            Rarity::Uncommon => 25.0,
+            Rarity::Rare => 10.0,
+            Rarity::Legendary => 5.0,
+        }
+    }
+
+    /// Stylesheet class of the upgrade button
+    pub fn class(&self) -> &'static str {
+        match self {
+            Rarity::Common => "common",
+            Rarity::Uncommon => "uncommon",
+            Rarity::Rare => "rare",
+            Rarity::Legendary => "legendary",
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::math::vec3;
+use bevy::prelude::*;
+
+use crate::characters::monsters::Monster;
+use crate::collision::EnemyKdTree;
+use crate::common::damage::{roll_crit, DamageEvent};
+use crate::common::motion::KnockbackEvent;
+use crate::common::stats::{Stat, Stats};
+use crate::common::status::ApplyStatusEvent;
+use crate::consts::*;
+use crate::gun::GunTimer;
+use crate::player::Player;
+use crate::resources::GlobalTextureAtlas;
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::weapons::inventory::Weapon;
+use crate::weapons::manifest::{WeaponData, WeaponManifest};
+
+pub(crate) struct AuraPlugin;
+
+/// Persistent area around the player hitting every monster inside it on each tick.
+#[derive(Component)]
+pub(crate) struct Aura {
+    pub radius: f32,
+}
+
+impl Plugin for AuraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    update_aura_transform,
+                    handle_aura_damage,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+pub(crate) fn insert_aura(entity: &mut EntityCommands, weapon: &WeaponData, radius: f32, handle: &GlobalTextureAtlas) {
+    entity.insert((
+        SpriteSheetBundle {
+            texture: handle.image.clone().unwrap(),
+            atlas: TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: weapon.sprite_index,
+            },
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, AURA_ALPHA),
+                ..default()
+            },
+            transform: Transform::from_scale(Vec3::splat(radius * 2.0 / TILE_W as f32))
+                .with_translation(vec3(0.0, 0.0, AURA_Z_INDEX)),
+            ..default()
+        },
+        Aura { radius },
+    ));
+}
+
+fn update_aura_transform(
+    player_query: Query<&Transform, With<Player>>,
+    mut aura_query: Query<&mut Transform, (With<Aura>, Without<Player>)>,
+) {
+    if player_query.is_empty() || aura_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation;
+    for mut transform in aura_query.iter_mut() {
+        transform.translation = vec3(player_pos.x, player_pos.y, AURA_Z_INDEX);
+    }
+}
+
+fn handle_aura_damage(
+    time: Res<Time>,
+    tree: Res<EnemyKdTree>,
+    weapon_manifest: Res<WeaponManifest>,
+    player_query: Query<&Stats, With<Player>>,
+    mut aura_query: Query<(&Transform, &mut GunTimer, &Weapon, &Aura, Entity)>,
+    enemy_query: Query<(), With<Monster>>,
+    mut ew: EventWriter<DamageEvent>,
+    mut knockback_ew: EventWriter<KnockbackEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() || aura_query.is_empty() {
+        return;
+    }
+
+    let stats = player_query.single();
+    let rng = &mut run_rng.combat;
+
+    for (transform, mut timer, weapon, aura, entity) in aura_query.iter_mut() {
+        let level = weapon.level;
+        let Some(weapon) = weapon_manifest.0.get(&weapon.id) else {
+            continue;
+        };
+        timer.0.tick(time.delta());
+
+        if timer.0.elapsed_secs() < weapon.cooldown * stats.get(Stat::Cooldown) {
+            continue;
+        }
+        timer.0.reset();
+
+        let pos = transform.translation;
+        for e in tree.0.within_radius(&[pos.x, pos.y], aura.radius) {
+            if enemy_query.contains(e.entity) {
+                let (amount, crit) = roll_crit(weapon.damage_at(level) * stats.get(Stat::Damage), weapon.crit_chance, weapon.crit_multiplier, rng);
+                ew.send(DamageEvent {
+                    target: e.entity,
+                    amount,
+                    source: entity,
+                    kind: weapon.damage_kind,
+                    crit,
+                    over_time: false,
+                });
+                knockback_ew.send(KnockbackEvent {
+                    target: e.entity,
+                    direction: e.pos - pos.truncate(),
+                    force: weapon.knockback,
+                });
+                for effect in weapon.on_hit.iter() {
+                    status_ew.send(ApplyStatusEvent {
+                        target: e.entity,
+                        effect: *effect,
+                        source: entity,
+                    });
+                }
+            }
+        }
+    }
+}
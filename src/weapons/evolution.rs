@@ -0,0 +1,162 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use leafwing_manifest::asset_state::SimpleAssetState;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use leafwing_manifest::plugin::RegisterManifest;
+use serde::{Deserialize, Serialize};
+
+use crate::upgrades::passives::{PassiveData, PassiveManifest, Passives};
+use crate::weapons::inventory::Weapon;
+use crate::weapons::manifest::{WeaponData, WeaponManifest};
+
+pub(crate) struct EvolutionPlugin;
+
+impl Plugin for EvolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_manifest::<EvolutionManifest>("weapons/evolutions.ron")
+            .add_event::<WeaponEvolved>()
+            .add_systems(OnEnter(SimpleAssetState::Ready), validate_evolutions);
+    }
+}
+
+/// Sent when an equipped weapon is replaced by its evolved form.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct WeaponEvolved {
+    pub weapon: Id<WeaponData>,
+    pub evolved: Id<WeaponData>,
+}
+
+/// A max level `weapon` held together with `passive` can evolve into `evolved`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct EvolutionData {
+    pub name: String,
+    pub weapon: Id<WeaponData>,
+    pub passive: Id<PassiveData>,
+    pub evolved: Id<WeaponData>,
+    weapon_name: String,
+    passive_name: String,
+    evolved_name: String,
+}
+
+impl EvolutionData {
+    /// Whether the player owns both ingredients, the weapon being at its max level
+    pub fn is_ready(&self, weapon: &Weapon, weapon_data: &WeaponData, passives: &Passives) -> bool {
+        weapon.id == self.weapon
+            && weapon.level >= weapon_data.max_level
+            && passives.level(self.passive) > 0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawEvolutionData {
+    name: String,
+    weapon: String,
+    passive: String,
+    evolved: String,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct EvolutionManifest(pub HashMap<Id<EvolutionData>, EvolutionData>);
+
+impl EvolutionManifest {
+    /// Evolved weapons are only obtained through their recipe, they are offered as level ups once owned
+    pub fn is_evolved_form(&self, weapon_id: Id<WeaponData>) -> bool {
+        self.0.values().any(|evolution| evolution.evolved == weapon_id)
+    }
+}
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawEvolutionManifest(Vec<RawEvolutionData>);
+
+#[derive(Debug)]
+pub(crate) enum EvolutionManifestError {
+    /// The recipe evolves a weapon into itself
+    EvolvesIntoItself(String),
+    /// Two recipes start from the same weapon
+    DuplicateWeapon(String),
+}
+
+impl fmt::Display for EvolutionManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvolutionManifestError::EvolvesIntoItself(name) => write!(f, "evolution {} evolves a weapon into itself", name),
+            EvolutionManifestError::DuplicateWeapon(weapon) => write!(f, "weapon {} has more than one evolution", weapon),
+        }
+    }
+}
+
+impl std::error::Error for EvolutionManifestError {}
+
+impl Manifest for EvolutionManifest {
+    type RawManifest = RawEvolutionManifest;
+    type RawItem = RawEvolutionData;
+    type Item = EvolutionData;
+    type ConversionError = EvolutionManifestError;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let mut weapons = HashSet::new();
+        let mut evolutions = HashMap::new();
+
+        for raw_item in raw_manifest.0 {
+            if raw_item.weapon == raw_item.evolved {
+                return Err(EvolutionManifestError::EvolvesIntoItself(raw_item.name));
+            }
+            if !weapons.insert(raw_item.weapon.clone()) {
+                return Err(EvolutionManifestError::DuplicateWeapon(raw_item.weapon));
+            }
+
+            let item = EvolutionData {
+                weapon: Id::from_name(&raw_item.weapon),
+                passive: Id::from_name(&raw_item.passive),
+                evolved: Id::from_name(&raw_item.evolved),
+                name: raw_item.name,
+                weapon_name: raw_item.weapon,
+                passive_name: raw_item.passive,
+                evolved_name: raw_item.evolved,
+            };
+
+            evolutions.insert(Id::from_name(&item.name), item);
+        }
+
+        Ok(EvolutionManifest(evolutions))
+    }
+
+    fn get(&self, id: Id<EvolutionData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
+
+/// Drops recipes naming a weapon or passive missing from its manifest.
+fn validate_evolutions(
+    mut evolution_manifest: ResMut<EvolutionManifest>,
+    weapon_manifest: Res<WeaponManifest>,
+    passive_manifest: Res<PassiveManifest>,
+) {
+    evolution_manifest.0.retain(|_, evolution| {
+        let mut valid = true;
+
+        if !weapon_manifest.0.contains_key(&evolution.weapon) {
+            error!("Evolution {} requires unknown weapon {}", evolution.name, evolution.weapon_name);
+            valid = false;
+        }
+        if !passive_manifest.0.contains_key(&evolution.passive) {
+            error!("Evolution {} requires unknown passive {}", evolution.name, evolution.passive_name);
+            valid = false;
+        }
+        if !weapon_manifest.0.contains_key(&evolution.evolved) {
+            error!("Evolution {} evolves into unknown weapon {}", evolution.name, evolution.evolved_name);
+            valid = false;
+        }
+
+        valid
+    });
+}
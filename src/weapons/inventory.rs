@@ -0,0 +1,125 @@
+use std::f32::consts::PI;
+
+use bevy::math::vec2;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use leafwing_manifest::identifier::Id;
+
+use crate::collision::EnemyKdTree;
+use crate::consts::*;
+use crate::gun::{insert_gun, Gun, GunTimer};
+use crate::resources::GlobalTextureAtlas;
+use crate::weapons::aura::{insert_aura, Aura};
+use crate::weapons::manifest::{Targeting, WeaponData, WeaponKind};
+use crate::weapons::melee::{insert_melee, MeleeSweep};
+use crate::weapons::orbit::{insert_orbit, Orbit};
+use crate::world::GameEntity;
+
+/// Weapon held in an inventory slot, whatever its kind.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Weapon {
+    pub id: Id<WeaponData>,
+    /// Starts at 1, raised by level up upgrades up to `WeaponData::max_level`
+    pub level: u32,
+}
+
+/// Offset of a weapon slot relative to the player, around which the weapon orbits while aiming.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct WeaponMount(pub Vec2);
+
+/// Weapon entities equipped by the player, one per slot (up to `MAX_WEAPON_SLOTS`).
+#[derive(Component, Default)]
+pub(crate) struct WeaponInventory(pub Vec<Entity>);
+
+impl WeaponInventory {
+    pub fn is_full(&self) -> bool {
+        self.0.len() >= MAX_WEAPON_SLOTS
+    }
+}
+
+/// Spawns a weapon entity in the next free slot of the inventory.
+/// Returns `None` when every slot is already taken.
+pub(crate) fn equip_weapon(
+    commands: &mut Commands,
+    inventory: &mut WeaponInventory,
+    weapon_id: Id<WeaponData>,
+    weapon: &WeaponData,
+    handle: &GlobalTextureAtlas,
+) -> Option<Entity> {
+    if inventory.is_full() {
+        return None;
+    }
+
+    let slot = inventory.0.len();
+    let mut entity = commands.spawn((
+        Weapon { id: weapon_id, level: 1 },
+        GunTimer(Stopwatch::new()),
+        WeaponMount(weapon_mount_offset(slot)),
+        weapon.targeting,
+        GameEntity,
+    ));
+
+    insert_weapon_kind(&mut entity, weapon, handle);
+
+    let weapon_entity = entity.id();
+    inventory.0.push(weapon_entity);
+
+    Some(weapon_entity)
+}
+
+/// Turns an equipped weapon into its evolved form. The entity, and so its slot and cooldown
+/// timer, is kept: only the kind specific components are swapped.
+pub(crate) fn evolve_weapon(
+    commands: &mut Commands,
+    weapon_entity: Entity,
+    weapon: &mut Weapon,
+    evolved_id: Id<WeaponData>,
+    evolved: &WeaponData,
+    handle: &GlobalTextureAtlas,
+) {
+    weapon.id = evolved_id;
+    weapon.level = 1;
+
+    let mut entity = commands.entity(weapon_entity);
+    entity
+        .despawn_descendants()
+        .remove::<(SpriteSheetBundle, Gun, Aura, Orbit, MeleeSweep)>()
+        .insert(evolved.targeting);
+    insert_weapon_kind(&mut entity, evolved, handle);
+}
+
+fn insert_weapon_kind(entity: &mut EntityCommands, weapon: &WeaponData, handle: &GlobalTextureAtlas) {
+    match weapon.kind {
+        WeaponKind::Gun => insert_gun(entity, weapon, handle),
+        WeaponKind::Aura { radius } => insert_aura(entity, weapon, radius, handle),
+        WeaponKind::Orbit { count, radius, angular_speed } => insert_orbit(entity, weapon, count, radius, angular_speed, handle),
+        WeaponKind::Melee { range, arc } => insert_melee(entity, range, arc),
+    }
+}
+
+/// The first slot sits on the player, the others are spread on a circle around it.
+fn weapon_mount_offset(slot: usize) -> Vec2 {
+    if slot == 0 {
+        return Vec2::ZERO;
+    }
+
+    let angle = (slot - 1) as f32 * PI * 2.0 / (MAX_WEAPON_SLOTS - 1) as f32;
+    vec2(angle.cos(), angle.sin()) * WEAPON_MOUNT_RADIUS
+}
+
+/// Point a weapon should aim at, `None` if its targeting rule has nothing to aim at.
+pub(crate) fn find_aim_target(
+    targeting: Targeting,
+    pivot: Vec2,
+    cursor_pos: Option<Vec2>,
+    tree: &EnemyKdTree,
+) -> Option<Vec2> {
+    match targeting {
+        Targeting::Cursor => cursor_pos,
+        Targeting::NearestEnemy => tree.0
+            .nearest(&[pivot.x, pivot.y])
+            .filter(|nearest| nearest.squared_distance <= AUTO_TARGET_RANGE * AUTO_TARGET_RANGE)
+            .map(|nearest| nearest.item.pos),
+    }
+}
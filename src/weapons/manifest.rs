@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_manifest::identifier::Id;
+use leafwing_manifest::manifest::{Manifest, ManifestFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::common::damage::DamageKind;
+use crate::common::status::StatusEffectData;
+use crate::consts::*;
+use crate::upgrades::rarity::Rarity;
+use crate::weapons::projectile::{ExplosionData, HomingData};
+
+/// How an equipped weapon picks the direction it fires in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component, Serialize, Deserialize)]
+pub(crate) enum Targeting {
+    /// Aims at the cursor and fires while the left mouse button is held
+    #[default]
+    Cursor,
+    /// Aims at the closest monster and fires on its own
+    NearestEnemy,
+}
+
+/// What kind of entity the weapon spawns, and how it deals its damage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum WeaponKind {
+    /// Fires projectiles, see the `projectile_*` fields
+    #[default]
+    Gun,
+    /// Damages every monster around the player each time the cooldown elapses
+    Aura { radius: f32 },
+    /// Blades circling the player, damaging the monsters they touch each time the cooldown elapses
+    Orbit { count: usize, radius: f32, angular_speed: f32 },
+    /// Sweeps an arc (in radians) in the aim direction each time the cooldown elapses
+    Melee { range: f32, arc: f32 },
+}
+
+#[derive(Debug, PartialEq, Component)]
+pub(crate) struct WeaponData {
+    pub name: String,
+    pub kind: WeaponKind,
+    pub rarity: Rarity,
+    /// Level reachable through level up upgrades
+    pub max_level: u32,
+    pub damage: f32,
+    /// Decides the color of the damage numbers
+    pub damage_kind: DamageKind,
+    /// Chance (0.0 to 1.0) of a hit being critical
+    pub crit_chance: f32,
+    /// Damage multiplier of critical hits
+    pub crit_multiplier: f32,
+    /// Seconds between two shots (or ticks for non projectile weapons)
+    pub cooldown: f32,
+    pub projectile_speed: f32,
+    /// Seconds before a projectile despawns
+    pub projectile_lifetime: f32,
+    pub projectiles_per_shot: usize,
+    /// Maximum angle (in radians) a projectile can deviate from the aim direction
+    pub spread_angle: f32,
+    /// Index of the weapon sprite in the global texture atlas
+    pub sprite_index: usize,
+    /// Index of the projectile sprite in the global texture atlas
+    pub projectile_sprite_index: usize,
+    pub targeting: Targeting,
+    /// Force pushing monsters back on hit
+    pub knockback: f32,
+    /// Number of monsters a projectile passes through
+    pub pierce: u32,
+    /// Number of times a projectile jumps to the next nearest monster
+    pub bounce: u32,
+    pub homing: Option<HomingData>,
+    pub explosion: Option<ExplosionData>,
+    /// Status effects applied to the monsters hit
+    pub on_hit: Vec<StatusEffectData>,
+}
+
+impl WeaponData {
+    /// Damage of a hit once the weapon reached `level`
+    pub fn damage_at(&self, level: u32) -> f32 {
+        self.damage * level_damage_multiplier(level)
+    }
+
+    /// Explosion of the projectiles once the weapon reached `level`, its damage grows like hits do
+    pub fn explosion_at(&self, level: u32) -> Option<ExplosionData> {
+        self.explosion.map(|explosion| ExplosionData {
+            damage: explosion.damage * level_damage_multiplier(level),
+            ..explosion
+        })
+    }
+}
+
+fn level_damage_multiplier(level: u32) -> f32 {
+    1.0 + level.saturating_sub(1) as f32 * WEAPON_LEVEL_DAMAGE_BONUS
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawWeaponData {
+    name: String,
+    #[serde(default)]
+    kind: WeaponKind,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default = "default_max_level")]
+    max_level: u32,
+    damage: f32,
+    #[serde(default)]
+    damage_kind: DamageKind,
+    #[serde(default)]
+    crit_chance: f32,
+    #[serde(default = "default_crit_multiplier")]
+    crit_multiplier: f32,
+    cooldown: f32,
+    #[serde(default)]
+    projectile_speed: f32,
+    #[serde(default)]
+    projectile_lifetime: f32,
+    #[serde(default)]
+    projectiles_per_shot: usize,
+    #[serde(default)]
+    spread_angle: f32,
+    sprite_index: usize,
+    #[serde(default)]
+    projectile_sprite_index: usize,
+    #[serde(default)]
+    targeting: Targeting,
+    #[serde(default)]
+    knockback: f32,
+    #[serde(default)]
+    pierce: u32,
+    #[serde(default)]
+    bounce: u32,
+    #[serde(default)]
+    homing: Option<HomingData>,
+    #[serde(default)]
+    explosion: Option<ExplosionData>,
+    #[serde(default)]
+    on_hit: Vec<StatusEffectData>,
+}
+
+fn default_max_level() -> u32 {
+    5
+}
+
+fn default_crit_multiplier() -> f32 {
+    2.0
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub(crate) struct WeaponManifest(pub HashMap<Id<WeaponData>, WeaponData>);
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawWeaponManifest(Vec<RawWeaponData>);
+
+impl Manifest for WeaponManifest {
+    type RawManifest = RawWeaponManifest;
+    type RawItem = RawWeaponData;
+    type Item = WeaponData;
+    type ConversionError = std::convert::Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Ron;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut World,
+    ) -> Result<Self, Self::ConversionError> {
+        let weapons: HashMap<_, _> = raw_manifest
+            .0
+            .into_iter()
+            .map(|raw_item| {
+                let item = WeaponData {
+                    name: raw_item.name,
+                    kind: raw_item.kind,
+                    rarity: raw_item.rarity,
+                    max_level: raw_item.max_level,
+                    damage: raw_item.damage,
+                    damage_kind: raw_item.damage_kind,
+                    crit_chance: raw_item.crit_chance,
+                    crit_multiplier: raw_item.crit_multiplier,
+                    cooldown: raw_item.cooldown,
+                    projectile_speed: raw_item.projectile_speed,
+                    projectile_lifetime: raw_item.projectile_lifetime,
+                    projectiles_per_shot: raw_item.projectiles_per_shot,
+                    spread_angle: raw_item.spread_angle,
+                    sprite_index: raw_item.sprite_index,
+                    projectile_sprite_index: raw_item.projectile_sprite_index,
+                    targeting: raw_item.targeting,
+                    knockback: raw_item.knockback,
+                    pierce: raw_item.pierce,
+                    bounce: raw_item.bounce,
+                    homing: raw_item.homing,
+                    explosion: raw_item.explosion,
+                    on_hit: raw_item.on_hit,
+                };
+
+                let id = Id::from_name(&item.name);
+
+                (id, item)
+            })
+            .collect();
+
+        Ok(WeaponManifest(weapons))
+    }
+
+    fn get(&self, id: Id<WeaponData>) -> Option<&Self::Item> {
+        self.0.get(&id)
+    }
+}
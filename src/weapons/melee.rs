@@ -0,0 +1,159 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use crate::characters::monsters::Monster;
+use crate::collision::EnemyKdTree;
+use crate::common::damage::{roll_crit, DamageEvent};
+use crate::common::motion::KnockbackEvent;
+use crate::common::stats::{Stat, Stats};
+use crate::common::status::ApplyStatusEvent;
+use crate::consts::*;
+use crate::gun::GunTimer;
+use crate::player::Player;
+use crate::resources::{CursorPosition, GlobalTextureAtlas};
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::weapons::inventory::{find_aim_target, Weapon};
+use crate::weapons::manifest::{Targeting, WeaponManifest};
+use crate::world::GameEntity;
+
+pub(crate) struct MeleePlugin;
+
+/// Sweeps an arc in front of the player each time the weapon cooldown elapses.
+#[derive(Component)]
+pub(crate) struct MeleeSweep {
+    pub range: f32,
+    /// Total angle of the sweep, in radians
+    pub arc: f32,
+}
+
+/// Short-lived sprite showing where a sweep landed.
+#[derive(Component)]
+struct SweepEffect(Timer);
+
+impl Plugin for MeleePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    handle_melee_sweeps,
+                    despawn_sweep_effects,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+pub(crate) fn insert_melee(entity: &mut EntityCommands, range: f32, arc: f32) {
+    entity.insert(MeleeSweep { range, arc });
+}
+
+fn handle_melee_sweeps(
+    mut commands: Commands,
+    time: Res<Time>,
+    cursor_pos: Res<CursorPosition>,
+    tree: Res<EnemyKdTree>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    handle: Res<GlobalTextureAtlas>,
+    weapon_manifest: Res<WeaponManifest>,
+    player_query: Query<(&Transform, &Stats), With<Player>>,
+    mut melee_query: Query<(&mut GunTimer, &Weapon, &MeleeSweep, &Targeting, Entity)>,
+    enemy_query: Query<(), With<Monster>>,
+    mut ew: EventWriter<DamageEvent>,
+    mut knockback_ew: EventWriter<KnockbackEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() || melee_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, stats) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+    let rng = &mut run_rng.combat;
+
+    for (mut timer, weapon, sweep, targeting, entity) in melee_query.iter_mut() {
+        let level = weapon.level;
+        let Some(weapon) = weapon_manifest.0.get(&weapon.id) else {
+            continue;
+        };
+        timer.0.tick(time.delta());
+
+        if timer.0.elapsed_secs() < weapon.cooldown * stats.get(Stat::Cooldown) {
+            continue;
+        }
+
+        let Some(target) = find_aim_target(*targeting, player_pos, cursor_pos.0, &tree) else {
+            continue;
+        };
+        if *targeting == Targeting::Cursor && !mouse_button_input.pressed(MouseButton::Left) {
+            continue;
+        }
+        timer.0.reset();
+
+        let direction = match (target - player_pos).try_normalize() {
+            Some(direction) => direction,
+            None => Vec2::X,
+        };
+
+        for e in tree.0.within_radius(&[player_pos.x, player_pos.y], sweep.range) {
+            if !enemy_query.contains(e.entity) {
+                continue;
+            }
+
+            let to_enemy = e.pos - player_pos;
+            if to_enemy == Vec2::ZERO || direction.angle_between(to_enemy).abs() <= sweep.arc / 2.0 {
+                let (amount, crit) = roll_crit(weapon.damage_at(level) * stats.get(Stat::Damage), weapon.crit_chance, weapon.crit_multiplier, rng);
+                ew.send(DamageEvent {
+                    target: e.entity,
+                    amount,
+                    source: entity,
+                    kind: weapon.damage_kind,
+                    crit,
+                    over_time: false,
+                });
+                knockback_ew.send(KnockbackEvent {
+                    target: e.entity,
+                    direction: e.pos - player_pos,
+                    force: weapon.knockback,
+                });
+                for effect in weapon.on_hit.iter() {
+                    status_ew.send(ApplyStatusEvent {
+                        target: e.entity,
+                        effect: *effect,
+                        source: entity,
+                    });
+                }
+            }
+        }
+
+        let effect_pos = player_pos + direction * sweep.range * 0.5;
+        commands.spawn((
+            SpriteSheetBundle {
+                texture: handle.image.clone().unwrap(),
+                atlas: TextureAtlas {
+                    layout: handle.layout.clone().unwrap(),
+                    index: weapon.sprite_index,
+                },
+                transform: Transform::from_translation(effect_pos.extend(GUN_Z_INDEX))
+                    .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x)))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                ..default()
+            },
+            SweepEffect(Timer::from_seconds(MELEE_EFFECT_DURATION, TimerMode::Once)),
+            GameEntity,
+        ));
+    }
+}
+
+fn despawn_sweep_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut effect_query: Query<(&mut SweepEffect, Entity)>,
+) {
+    for (mut effect, e) in effect_query.iter_mut() {
+        if effect.0.tick(time.delta()).just_finished() {
+            commands.entity(e).despawn();
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub(crate) mod manifest;
+pub(crate) mod inventory;
+pub(crate) mod projectile;
+pub(crate) mod aura;
+pub(crate) mod orbit;
+pub(crate) mod melee;
+pub(crate) mod evolution;
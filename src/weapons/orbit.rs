@@ -0,0 +1,157 @@
+use std::f32::consts::PI;
+
+use bevy::ecs::system::EntityCommands;
+use bevy::math::vec3;
+use bevy::prelude::*;
+
+use crate::characters::monsters::Monster;
+use crate::collision::EnemyKdTree;
+use crate::common::damage::{roll_crit, DamageEvent};
+use crate::common::motion::KnockbackEvent;
+use crate::common::stats::{Stat, Stats};
+use crate::common::status::ApplyStatusEvent;
+use crate::consts::*;
+use crate::gun::GunTimer;
+use crate::player::Player;
+use crate::resources::GlobalTextureAtlas;
+use crate::rng::RunRng;
+use crate::state::GameState;
+use crate::weapons::inventory::Weapon;
+use crate::weapons::manifest::{WeaponData, WeaponManifest};
+
+pub(crate) struct OrbitPlugin;
+
+/// Pivot following the player, its `OrbitBlade` children circle around it.
+#[derive(Component)]
+pub(crate) struct Orbit {
+    /// Rotation speed of the blades, in radians per second
+    pub angular_speed: f32,
+}
+
+#[derive(Component)]
+pub(crate) struct OrbitBlade;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    rotate_orbits,
+                    handle_orbit_damage,
+                ).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+pub(crate) fn insert_orbit(
+    entity: &mut EntityCommands,
+    weapon: &WeaponData,
+    count: usize,
+    radius: f32,
+    angular_speed: f32,
+    handle: &GlobalTextureAtlas,
+) {
+    entity
+        .insert((
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, GUN_Z_INDEX)),
+            Orbit { angular_speed },
+        ))
+        .with_children(|parent| {
+            for i in 0..count {
+                let angle = i as f32 * PI * 2.0 / count as f32;
+                parent.spawn((
+                    SpriteSheetBundle {
+                        texture: handle.image.clone().unwrap(),
+                        atlas: TextureAtlas {
+                            layout: handle.layout.clone().unwrap(),
+                            index: weapon.sprite_index,
+                        },
+                        transform: Transform::from_translation(vec3(angle.cos() * radius, angle.sin() * radius, 0.0))
+                            .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+                        ..default()
+                    },
+                    OrbitBlade,
+                ));
+            }
+        });
+}
+
+fn rotate_orbits(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    mut orbit_query: Query<(&mut Transform, &Orbit), Without<Player>>,
+) {
+    if player_query.is_empty() || orbit_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation;
+    for (mut transform, orbit) in orbit_query.iter_mut() {
+        transform.translation = vec3(player_pos.x, player_pos.y, GUN_Z_INDEX);
+        transform.rotate_z(orbit.angular_speed * time.delta_seconds());
+    }
+}
+
+fn handle_orbit_damage(
+    time: Res<Time>,
+    tree: Res<EnemyKdTree>,
+    weapon_manifest: Res<WeaponManifest>,
+    player_query: Query<&Stats, With<Player>>,
+    mut orbit_query: Query<(&mut GunTimer, &Weapon, &Children, Entity), With<Orbit>>,
+    blade_query: Query<&GlobalTransform, With<OrbitBlade>>,
+    enemy_query: Query<(), With<Monster>>,
+    mut ew: EventWriter<DamageEvent>,
+    mut knockback_ew: EventWriter<KnockbackEvent>,
+    mut status_ew: EventWriter<ApplyStatusEvent>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    if player_query.is_empty() || orbit_query.is_empty() {
+        return;
+    }
+
+    let stats = player_query.single();
+    let rng = &mut run_rng.combat;
+
+    for (mut timer, weapon, children, entity) in orbit_query.iter_mut() {
+        let level = weapon.level;
+        let Some(weapon) = weapon_manifest.0.get(&weapon.id) else {
+            continue;
+        };
+        timer.0.tick(time.delta());
+
+        if timer.0.elapsed_secs() < weapon.cooldown * stats.get(Stat::Cooldown) {
+            continue;
+        }
+        timer.0.reset();
+
+        for blade_transform in blade_query.iter_many(children) {
+            let pos = blade_transform.translation();
+            for e in tree.0.within_radius(&[pos.x, pos.y], ORBIT_BLADE_HIT_RADIUS) {
+                if enemy_query.contains(e.entity) {
+                    let (amount, crit) = roll_crit(weapon.damage_at(level) * stats.get(Stat::Damage), weapon.crit_chance, weapon.crit_multiplier, rng);
+                    ew.send(DamageEvent {
+                        target: e.entity,
+                        amount,
+                        source: entity,
+                        kind: weapon.damage_kind,
+                        crit,
+                        over_time: false,
+                    });
+                    knockback_ew.send(KnockbackEvent {
+                        target: e.entity,
+                        direction: e.pos - pos.truncate(),
+                        force: weapon.knockback,
+                    });
+                    for effect in weapon.on_hit.iter() {
+                        status_ew.send(ApplyStatusEvent {
+                            target: e.entity,
+                            effect: *effect,
+                            source: entity,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
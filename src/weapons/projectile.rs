@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::collision::EnemyKdTree;
+use crate::common::status::StatusEffectData;
+use crate::gun::BulletDirection;
+use crate::state::GameState;
+
+pub(crate) struct ProjectilePlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct HomingData {
+    /// Maximum rotation of the projectile, in radians per second
+    pub turn_rate: f32,
+    /// Distance under which a monster can be locked on
+    pub range: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ExplosionData {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+/// Number of monsters the projectile can pass through before being despawned.
+#[derive(Component)]
+pub(crate) struct Pierce(pub u32);
+
+/// Number of times the projectile redirects itself to the next nearest monster after a hit.
+#[derive(Component)]
+pub(crate) struct Bounce(pub u32);
+
+/// Steers the projectile towards the nearest monster.
+#[derive(Component)]
+pub(crate) struct Homing(pub HomingData);
+
+/// Deals area damage when the projectile hits a monster or expires, scaled like the projectile
+/// damage when it is fired.
+#[derive(Component)]
+pub(crate) struct Explosive(pub ExplosionData);
+
+/// Status effects applied to every monster the projectile hits.
+#[derive(Component)]
+pub(crate) struct OnHitEffects(pub Vec<StatusEffectData>);
+
+/// Monsters already hit by a projectile, so pierce and bounce never hit the same one twice.
+#[derive(Component, Default)]
+pub(crate) struct HitList(pub Vec<Entity>);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct ExplosionEvent {
+    pub position: Vec2,
+    pub radius: f32,
+    pub damage: f32,
+    pub knockback: f32,
+    pub source: Entity,
+}
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<ExplosionEvent>()
+            .add_systems(
+                Update,
+                steer_homing_projectiles.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn steer_homing_projectiles(
+    time: Res<Time>,
+    tree: Res<EnemyKdTree>,
+    mut projectile_query: Query<(&Transform, &mut BulletDirection, &Homing, Option<&HitList>)>,
+) {
+    if projectile_query.is_empty() {
+        return;
+    }
+
+    for (transform, mut direction, homing, hit_list) in projectile_query.iter_mut() {
+        let pos = transform.translation.truncate();
+        let target = tree.0
+            .within_radius(&[pos.x, pos.y], homing.0.range)
+            .into_iter()
+            .filter(|c| hit_list.map_or(true, |hit_list| !hit_list.0.contains(&c.entity)))
+            .min_by(|a, b| a.pos.distance_squared(pos).total_cmp(&b.pos.distance_squared(pos)));
+
+        let Some(target) = target else {
+            continue;
+        };
+
+        let desired = (target.pos - pos).normalize_or_zero();
+        if desired == Vec2::ZERO {
+            continue;
+        }
+
+        let angle = direction.0.angle_between(desired);
+        let max_step = homing.0.turn_rate * time.delta_seconds();
+        let step = angle.clamp(-max_step, max_step);
+        direction.0 = Vec2::from_angle(step).rotate(direction.0).normalize();
+    }
+}
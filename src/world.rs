@@ -2,11 +2,9 @@ use bevy::math::vec3;
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
 use rand::Rng;
-use crate::animation::AnimationTimer;
 
 use crate::consts::*;
-use crate::player::{Health, Player, PlayerState};
-use crate::gun::{Gun, GunTimer};
+use crate::rng::{seed_run_rng, RunRng};
 use crate::state::GameState;
 use crate::resources::GlobalTextureAtlas;
 
@@ -15,55 +13,40 @@ pub struct WorldPlugin;
 #[derive(Component)]
 pub struct GameEntity;
 
+/// Time spent in the current run, paused outside of `GameState::InGame`.
+#[derive(Resource, Default)]
+pub struct RunTimer(pub Stopwatch);
+
+impl RunTimer {
+    pub fn elapsed_secs(&self) -> f32 {
+        self.0.elapsed_secs()
+    }
+}
+
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(OnEnter(GameState::GameInit), (init_world, spawn_world_decorations))
-            .add_systems(OnExit(GameState::InGame), despawn_all_game_entities);
+            .init_resource::<RunTimer>()
+            .add_systems(OnEnter(GameState::GameInit), (init_world, spawn_world_decorations.after(seed_run_rng), reset_run_timer))
+            .add_systems(Update, tick_run_timer.run_if(in_state(GameState::InGame)))
+            // Leaving `InGame` for a level up keeps the run alive, it only ends back on the main menu
+            .add_systems(OnEnter(GameState::MainMenu), despawn_all_game_entities);
     }
 }
 
+/// The player itself is spawned from the selected character, see `characters::player`.
 fn init_world(
-    mut commands: Commands,
-    handle: Res<GlobalTextureAtlas>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    commands.spawn((
-        SpriteSheetBundle {
-            texture: handle.image.clone().unwrap(),
-            atlas: TextureAtlas {
-                layout: handle.layout.clone().unwrap(),
-                index: 0,
-            },
-            transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)).with_translation(vec3(0.0, 0.0, PLAYER_Z_INDEX)),
-            ..default()
-        },
-        Player,
-        PlayerState::default(),
-        Health(PLAYER_MAX_HEALTH),
-        AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
-        GameEntity,
-    ));
-    commands.spawn((
-        SpriteSheetBundle {
-            texture: handle.image.clone().unwrap(),
-            atlas: TextureAtlas {
-                layout: handle.layout.clone().unwrap(),
-                index: 17,
-            },
-            transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)).with_translation(vec3(0.0, 0.0, GUN_Z_INDEX)),
-            ..default()
-        },
-        Gun,
-        GunTimer(Stopwatch::new()),
-        GameEntity,
-    ));
-
     next_state.set(GameState::InGame);
 }
 
-fn spawn_world_decorations(mut commands: Commands, handle: Res<GlobalTextureAtlas>) {
-    let mut rng = rand::thread_rng();
+fn spawn_world_decorations(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let rng = &mut run_rng.world;
     for _ in 0..NUM_WORLD_DECORATIONS {
         let x = rng.gen_range(-WORLD_W..WORLD_W);
         let y = rng.gen_range(-WORLD_H..WORLD_H);
@@ -83,6 +66,14 @@ fn spawn_world_decorations(mut commands: Commands, handle: Res<GlobalTextureAtla
     }
 }
 
+fn reset_run_timer(mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.reset();
+}
+
+fn tick_run_timer(time: Res<Time>, mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.tick(time.delta());
+}
+
 fn despawn_all_game_entities(
     mut commands: Commands,
     all_entities: Query<Entity, With<GameEntity>>,